@@ -0,0 +1,308 @@
+use std::time::{Duration, Instant};
+
+use kube::api::DeleteParams;
+use serde_yaml::{Mapping, Value};
+
+use crate::deploy::apply_resource;
+use crate::discovery::ApiCatalog;
+use crate::error::BoomError;
+use crate::hints::{annotation, parse_duration};
+use crate::hooks;
+use crate::manifest::ResourceDescriptor;
+use crate::monitor::{self, ResourceState};
+use crate::output;
+
+pub const CANARY_ANNOTATION: &str = "boom.io/canary";
+pub const CANARY_SOAK_ANNOTATION: &str = "boom.io/canary-soak";
+pub const CANARY_VERIFY_ANNOTATION: &str = "boom.io/canary-verify";
+// Added to the canary's selector and pod labels, while the primary Deployment's
+// selector excludes it (see `exclude_canary`). The two selectors then never
+// overlap, and Services selecting the shared labels still route to both.
+pub const TRACK_LABEL: &str = "boom.io/track";
+
+#[derive(Debug, Clone)]
+pub struct CanaryPlan {
+    pub deployment: ResourceDescriptor,
+    pub replicas: i64,
+    pub soak: Duration,
+    pub verify: Option<ResourceDescriptor>,
+}
+
+// "2" is an absolute pod count; "10%" is a share of spec.replicas, rounded up.
+pub fn canary_replicas(value: &str, desired: i64) -> Result<i64, String> {
+    let invalid =
+        || format!("invalid {CANARY_ANNOTATION} {value:?} (expected a pod count or a percentage)");
+    let replicas = match value.strip_suffix('%') {
+        Some(percent) => {
+            let percent: i64 = percent.parse().map_err(|_| invalid())?;
+            if !(1..=100).contains(&percent) {
+                return Err(invalid());
+            }
+            (desired * percent + 99) / 100
+        }
+        None => value.parse().map_err(|_| invalid())?,
+    };
+    if replicas < 1 {
+        return Err(invalid());
+    }
+    Ok(replicas)
+}
+
+// Pulls canary Deployments' verification Jobs out of the regular deploy; they
+// only ever run against a canary.
+pub fn plan(
+    resources: Vec<ResourceDescriptor>,
+) -> Result<(Vec<CanaryPlan>, Vec<ResourceDescriptor>), String> {
+    let mut plans = Vec::new();
+    let mut verify_jobs = Vec::new();
+    for resource in &resources {
+        let Some(value) = annotation(resource, CANARY_ANNOTATION) else {
+            continue;
+        };
+        let key = format!("{}/{}", resource.kind, resource.name);
+        if resource.kind != "Deployment" {
+            return Err(format!(
+                "{key}: {CANARY_ANNOTATION} is only supported on Deployments"
+            ));
+        }
+        let desired = resource.raw["spec"]["replicas"].as_i64().unwrap_or(1);
+        let replicas = canary_replicas(value, desired).map_err(|e| format!("{key}: {e}"))?;
+        let soak = annotation(resource, CANARY_SOAK_ANNOTATION)
+            .map(parse_duration)
+            .transpose()
+            .map_err(|e| format!("{key}: {CANARY_SOAK_ANNOTATION}: {e}"))?
+            .unwrap_or_default();
+        let verify = match annotation(resource, CANARY_VERIFY_ANNOTATION) {
+            Some(job) => {
+                let is_job = |r: &&ResourceDescriptor| {
+                    r.kind == "Job" && r.name == job && r.namespace == resource.namespace
+                };
+                let found = resources.iter().find(is_job).ok_or_else(|| {
+                    format!("{key}: {CANARY_VERIFY_ANNOTATION} names Job/{job}, not in this deploy")
+                })?;
+                verify_jobs.push(job.to_string());
+                Some(found.clone())
+            }
+            None => None,
+        };
+        plans.push(CanaryPlan {
+            deployment: resource.clone(),
+            replicas,
+            soak,
+            verify,
+        });
+    }
+
+    let remaining = resources
+        .into_iter()
+        .filter(|r| !(r.kind == "Job" && verify_jobs.contains(&r.name)))
+        .map(|mut r| {
+            if annotation(&r, CANARY_ANNOTATION).is_some() {
+                exclude_canary(&mut r.raw);
+            }
+            r
+        })
+        .collect();
+    Ok((plans, remaining))
+}
+
+// Adds `boom.io/track NotIn (canary)` to a Deployment's selector so it never
+// selects its canary's pods. This is done whether or not --canary is passed,
+// since a Deployment's selector can't change after creation.
+pub fn exclude_canary(deployment: &mut Value) {
+    let Some(selector) = deployment
+        .get_mut("spec")
+        .and_then(|spec| spec.get_mut("selector"))
+        .and_then(Value::as_mapping_mut)
+    else {
+        return;
+    };
+    let expressions = selector
+        .entry("matchExpressions".into())
+        .or_insert_with(|| Value::Sequence(Vec::new()));
+    if expressions.is_null() {
+        *expressions = Value::Sequence(Vec::new());
+    }
+    let Some(expressions) = expressions.as_sequence_mut() else {
+        return;
+    };
+    if expressions
+        .iter()
+        .any(|e| e["key"].as_str() == Some(TRACK_LABEL))
+    {
+        return;
+    }
+    let mut expression = Mapping::new();
+    expression.insert("key".into(), TRACK_LABEL.into());
+    expression.insert("operator".into(), "NotIn".into());
+    expression.insert("values".into(), Value::Sequence(vec!["canary".into()]));
+    expressions.push(Value::Mapping(expression));
+}
+
+fn insert_label(parent: &mut Value, path: &[&str]) {
+    let mut current = parent;
+    for key in path {
+        let Some(mapping) = current.as_mapping_mut() else {
+            return;
+        };
+        current = mapping
+            .entry((*key).into())
+            .or_insert_with(|| Value::Mapping(Mapping::new()));
+        if current.is_null() {
+            *current = Value::Mapping(Mapping::new());
+        }
+    }
+    if let Some(labels) = current.as_mapping_mut() {
+        labels.insert(TRACK_LABEL.into(), "canary".into());
+    }
+}
+
+// A scaled-down copy of the Deployment named `<name>-canary`.
+pub fn canary_copy(deployment: &ResourceDescriptor, replicas: i64) -> ResourceDescriptor {
+    let name = format!("{}-canary", deployment.name);
+    let mut raw = deployment.raw.clone();
+    raw["metadata"]["name"] = name.clone().into();
+    if let Some(annotations) = raw["metadata"]
+        .get_mut("annotations")
+        .and_then(Value::as_mapping_mut)
+    {
+        for key in [
+            CANARY_ANNOTATION,
+            CANARY_SOAK_ANNOTATION,
+            CANARY_VERIFY_ANNOTATION,
+        ] {
+            annotations.remove(key);
+        }
+    }
+    raw["spec"]["replicas"] = replicas.into();
+    insert_label(&mut raw, &["metadata", "labels"]);
+    insert_label(&mut raw, &["spec", "selector", "matchLabels"]);
+    insert_label(&mut raw, &["spec", "template", "metadata", "labels"]);
+
+    ResourceDescriptor {
+        name,
+        raw,
+        ..deployment.clone()
+    }
+}
+
+async fn delete_canary(client: &kube::Client, catalog: &ApiCatalog, canary: &ResourceDescriptor) {
+    let ns = canary.namespace.as_deref().unwrap_or("default");
+    let result = match catalog.api(client, &canary.api_version, &canary.kind, ns) {
+        Ok(api) => api
+            .delete(&canary.name, &DeleteParams::background())
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        output::warn(&format!(
+            "[boom] failed to delete canary {}/{}: {e}",
+            canary.kind, canary.name
+        ));
+    }
+}
+
+// Applies the canary, waits for it to become ready, soaks, re-checks its pods
+// and runs the verification Job.
+async fn run_stage(
+    client: &kube::Client,
+    catalog: &ApiCatalog,
+    plan: &CanaryPlan,
+    canary: &ResourceDescriptor,
+    timeout_secs: u64,
+) -> Result<(), BoomError> {
+    let key = format!("{}/{}", canary.kind, canary.name);
+    let api = catalog
+        .api(
+            client,
+            &canary.api_version,
+            &canary.kind,
+            canary.namespace.as_deref().unwrap_or("default"),
+        )
+        .map_err(BoomError::Apply)?;
+    apply_resource(&api, &canary.name, &canary.raw, false)
+        .await
+        .map_err(|e| BoomError::Apply(format!("{key}: {e}")))?;
+    output::applied(&canary.kind, &canary.name, None);
+
+    let waited = std::slice::from_ref(canary);
+    let unready = |state: ResourceState, stage: &str| match state {
+        ResourceState::NotReady => BoomError::Timeout(format!(
+            "canary {key} not ready {stage} after {timeout_secs}s"
+        )),
+        _ => BoomError::Failed(format!("canary {key} failed {stage}")),
+    };
+
+    let state = monitor::watch_resources(client, catalog, waited, timeout_secs).await;
+    if state != ResourceState::Ready {
+        return Err(unready(state, "before soak"));
+    }
+
+    if !plan.soak.is_zero() {
+        output::info(&format!(
+            "[boom] soaking canary {key} for {}s",
+            plan.soak.as_secs()
+        ));
+        tokio::time::sleep(plan.soak).await;
+        // Pods that crashed or were evicted during the soak show up here.
+        let state = monitor::watch_resources(client, catalog, waited, timeout_secs).await;
+        if state != ResourceState::Ready {
+            return Err(unready(state, "during soak"));
+        }
+    }
+
+    if let Some(job) = &plan.verify {
+        output::info(&format!(
+            "[boom] verifying canary {key} with Job/{}",
+            job.name
+        ));
+        let state = hooks::run_hook(client, catalog, job, timeout_secs).await?;
+        if state != ResourceState::Ready {
+            let diag = monitor::collect_diagnostics(client, job).await;
+            output::diagnostics(&format!("Job/{}", job.name), &diag);
+            return Err(unready(state, "verification"));
+        }
+    }
+    Ok(())
+}
+
+// Runs every canary stage; the canary copies are deleted whether they pass
+// (the regular deploy then promotes the new template) or not.
+pub async fn run(
+    client: &kube::Client,
+    catalog: &ApiCatalog,
+    plans: &[CanaryPlan],
+    timeout_secs: u64,
+    summary_rows: &mut Vec<(String, String, String, String)>,
+) -> Result<(), BoomError> {
+    for plan in plans {
+        let canary = canary_copy(&plan.deployment, plan.replicas);
+        output::info(&format!(
+            "[boom] starting canary {}/{} with {} replicas",
+            canary.kind, canary.name, plan.replicas
+        ));
+        let start = Instant::now();
+        let result = run_stage(client, catalog, plan, &canary, timeout_secs).await;
+        if result.is_err() {
+            let diag = monitor::collect_diagnostics(client, &canary).await;
+            output::diagnostics(&format!("{}/{}", canary.kind, canary.name), &diag);
+        }
+        delete_canary(client, catalog, &canary).await;
+
+        summary_rows.push((
+            canary.name.clone(),
+            canary.kind.clone(),
+            if result.is_ok() {
+                "Promoted"
+            } else {
+                "Aborted"
+            }
+            .to_string(),
+            format!("{:.1}s", start.elapsed().as_secs_f64()),
+        ));
+        result?;
+    }
+    Ok(())
+}
@@ -1,10 +1,9 @@
 use kube::config::{KubeConfigOptions, Kubeconfig};
 
-pub async fn build_client(context: Option<&str>) -> kube::Client {
-    let kubeconfig = Kubeconfig::read().unwrap_or_else(|e| {
-        eprintln!("boom: {e}");
-        std::process::exit(1);
-    });
+use crate::error::BoomError;
+
+pub async fn build_client(context: Option<&str>) -> Result<kube::Client, BoomError> {
+    let kubeconfig = Kubeconfig::read().map_err(|e| BoomError::Auth(e.to_string()))?;
 
     let options = KubeConfigOptions {
         context: context.map(String::from),
@@ -13,13 +12,7 @@ pub async fn build_client(context: Option<&str>) -> kube::Client {
 
     let config = kube::Config::from_custom_kubeconfig(kubeconfig, &options)
         .await
-        .unwrap_or_else(|e| {
-            eprintln!("boom: {e}");
-            std::process::exit(1);
-        });
+        .map_err(|e| BoomError::Auth(e.to_string()))?;
 
-    kube::Client::try_from(config).unwrap_or_else(|e| {
-        eprintln!("boom: {e}");
-        std::process::exit(1);
-    })
+    kube::Client::try_from(config).map_err(|e| BoomError::Auth(e.to_string()))
 }
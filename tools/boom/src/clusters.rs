@@ -0,0 +1,142 @@
+use std::future::Future;
+use std::path::Path;
+use std::time::Instant;
+
+use serde::Deserialize;
+
+use crate::error::BoomError;
+use crate::output;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClusterTarget {
+    pub context: String,
+    // Deep-merged over `--values` and `--bindings` for this cluster only.
+    #[serde(default)]
+    pub bindings: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ClusterTarget {
+    pub fn new(context: &str) -> Self {
+        Self {
+            context: context.to_string(),
+            bindings: serde_json::Map::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FanOut {
+    Parallel,
+    Sequential { fail_fast: bool },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ContextsFile {
+    clusters: Vec<ClusterTarget>,
+}
+
+pub fn parse_contexts(yaml: &str) -> Result<Vec<ClusterTarget>, String> {
+    let file: ContextsFile =
+        serde_yaml::from_str(yaml).map_err(|e| format!("invalid contexts file: {e}"))?;
+    Ok(file.clusters)
+}
+
+pub fn load_contexts_file(path: &Path) -> Result<Vec<ClusterTarget>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("cannot read contexts file {}: {e}", path.display()))?;
+    parse_contexts(&content).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+// `--context` values first, then the contexts file; a context may appear once.
+pub fn targets(
+    contexts: &[String],
+    file: Vec<ClusterTarget>,
+) -> Result<Vec<ClusterTarget>, String> {
+    let mut targets: Vec<ClusterTarget> = contexts.iter().map(|c| ClusterTarget::new(c)).collect();
+    targets.extend(file);
+    for (i, target) in targets.iter().enumerate() {
+        if targets[..i].iter().any(|t| t.context == target.context) {
+            return Err(format!("context {} given more than once", target.context));
+        }
+    }
+    Ok(targets)
+}
+
+// Deploys to every cluster and prints one summary table grouped by cluster. The
+// returned error is the first failing cluster's, so its exit code is kept.
+pub async fn run<'a, F, Fut>(
+    targets: &'a [ClusterTarget],
+    mode: FanOut,
+    deploy: F,
+) -> Result<(), BoomError>
+where
+    F: Fn(&'a ClusterTarget) -> Fut,
+    Fut: Future<Output = Result<(), BoomError>>,
+{
+    let start = Instant::now();
+    let scoped = |target: &'a ClusterTarget| {
+        let future = deploy(target);
+        async move {
+            output::info(&format!("[boom] deploying to context {}", target.context));
+            output::in_cluster(&target.context, future).await
+        }
+    };
+
+    let mut results: Vec<Option<(Result<(), BoomError>, Vec<_>)>> = Vec::new();
+    match mode {
+        FanOut::Parallel => {
+            let all = futures::future::join_all(targets.iter().map(scoped)).await;
+            results.extend(all.into_iter().map(Some));
+        }
+        FanOut::Sequential { fail_fast } => {
+            for target in targets {
+                let stop = results.iter().any(|r| matches!(r, Some((Err(_), _))));
+                if fail_fast && stop {
+                    results.push(None);
+                    continue;
+                }
+                results.push(Some(scoped(target).await));
+            }
+        }
+    }
+
+    let mut summary = Vec::new();
+    let mut first_error = None;
+    let mut failed = 0;
+    for (target, result) in targets.iter().zip(results) {
+        let Some((result, rows)) = result else {
+            summary.push((target.context.clone(), "skipped".to_string(), Vec::new()));
+            continue;
+        };
+        let status = match &result {
+            Ok(()) => "succeeded".to_string(),
+            Err(e) => {
+                failed += 1;
+                format!("failed ({})", e.kind())
+            }
+        };
+        summary.push((target.context.clone(), status, rows));
+        if let Err(e) = result
+            && first_error.is_none()
+        {
+            first_error = Some(e.with_context(&target.context));
+        }
+    }
+    output::cluster_summary_table(&summary);
+
+    if let Some(e) = first_error {
+        output::error(&format!(
+            "[boom] {failed} of {} clusters failed",
+            targets.len()
+        ));
+        return Err(e);
+    }
+    output::success(&format!(
+        "[boom] deployed to {} clusters in {:.1}s",
+        targets.len(),
+        start.elapsed().as_secs_f64()
+    ));
+    Ok(())
+}
@@ -1,25 +1,257 @@
 use std::time::Instant;
 
 use kube::Api;
-use kube::api::{ApiResource, DynamicObject, Patch, PatchParams};
+use kube::api::{DynamicObject, Patch, PatchParams};
 use tokio::task::JoinSet;
 
+use crate::canary::{self, CanaryPlan};
+use crate::discovery::ApiCatalog;
+use crate::error::BoomError;
+use crate::hints;
+use crate::hooks::{self, HookKind};
 use crate::manifest::{self, ResourceDescriptor};
 use crate::monitor::{self, ResourceState};
 use crate::output;
+use crate::phases;
+use crate::revert;
+use crate::validate;
 
-pub async fn run(
-    client: kube::Client,
+#[derive(Debug, Clone)]
+pub struct DeployOptions {
+    pub verify_result: bool,
+    pub global_timeout: u64,
+    pub rollback_on_failure: bool,
+    pub validate: bool,
+    pub canary: bool,
+}
+
+impl Default for DeployOptions {
+    fn default() -> Self {
+        Self {
+            verify_result: true,
+            global_timeout: 300,
+            rollback_on_failure: false,
+            validate: true,
+            canary: false,
+        }
+    }
+}
+
+// The manifests as deploy applies them: sorted, namespaced, with hooks and
+// canaries split out of the regular resources, and canary-annotated
+// Deployments' selectors rewritten by `canary::plan`. `diff` goes through the
+// same step so it previews exactly what deploy would send.
+#[derive(Debug)]
+pub struct Prepared {
+    pub pre_deploy: Vec<ResourceDescriptor>,
+    pub canaries: Vec<CanaryPlan>,
+    pub resources: Vec<ResourceDescriptor>,
+    pub post_deploy: Vec<ResourceDescriptor>,
+}
+
+pub fn prepare(
+    catalog: &ApiCatalog,
     namespace: &str,
     mut resources: Vec<ResourceDescriptor>,
-    verify_result: bool,
-    global_timeout: u64,
-) -> bool {
+) -> Result<Prepared, String> {
+    for resource in &resources {
+        hints::resource_hints(resource)?;
+    }
     manifest::sort_by_priority(&mut resources);
+    assign_namespaces(catalog, &mut resources, namespace);
+    let hooks::SplitHooks {
+        pre_deploy,
+        resources,
+        post_deploy,
+    } = hooks::split_hooks(resources)?;
+    let (canaries, resources) = canary::plan(resources)?;
+    Ok(Prepared {
+        pre_deploy,
+        canaries,
+        resources,
+        post_deploy,
+    })
+}
+
+pub async fn run(
+    client: kube::Client,
+    catalog: &ApiCatalog,
+    namespace: &str,
+    resources: Vec<ResourceDescriptor>,
+    options: &DeployOptions,
+) -> Result<(), BoomError> {
+    if options.validate {
+        validate::check(&client, &resources).await?;
+    }
+    let Prepared {
+        pre_deploy,
+        canaries,
+        resources,
+        post_deploy,
+    } = prepare(catalog, namespace, resources).map_err(BoomError::Render)?;
+    let phases = phases::plan(&resources).map_err(BoomError::Render)?;
+    let run_canaries = options.canary && !canaries.is_empty();
+    // Hooks and canaries run before the phases, so the ConfigMaps, Secrets and
+    // ServiceAccounts they mount have to be in place first.
+    let (dependencies, phases) = if run_canaries || !pre_deploy.is_empty() {
+        phases::split_dependencies(phases)
+    } else {
+        (Vec::new(), phases)
+    };
 
     let start = Instant::now();
-    let mut all_ok = true;
     let mut summary_rows: Vec<(String, String, String, String)> = Vec::new();
+    let mut applied: Vec<ResourceDescriptor> = Vec::new();
+
+    if !dependencies.is_empty() {
+        output::info(&format!(
+            "[boom] applying {} dependencies",
+            dependencies.len()
+        ));
+        let failed_applies = apply_tiers(&client, catalog, &dependencies, &mut summary_rows).await;
+        applied.extend(dependencies.iter().cloned());
+        if failed_applies > 0 {
+            output::summary_table(&summary_rows);
+            output::finished("failed");
+            return Err(BoomError::Apply(format!(
+                "{failed_applies} resources failed to apply"
+            )));
+        }
+        if options.verify_result {
+            let state =
+                monitor::watch_resources(&client, catalog, &dependencies, options.global_timeout)
+                    .await;
+            if state != ResourceState::Ready {
+                return Err(fail_unready(
+                    &client,
+                    catalog,
+                    &dependencies,
+                    &applied,
+                    state,
+                    summary_rows,
+                    options,
+                )
+                .await);
+            }
+        }
+    }
+
+    // Pre-deploy hooks (e.g. migrations) must succeed before any workload is applied.
+    if let Err(e) = hooks::run_hooks(
+        &client,
+        catalog,
+        HookKind::PreDeploy,
+        &pre_deploy,
+        options.global_timeout,
+        &mut summary_rows,
+    )
+    .await
+    {
+        return Err(fail_hook(&summary_rows, e));
+    }
+
+    if run_canaries
+        && let Err(e) = canary::run(
+            &client,
+            catalog,
+            &canaries,
+            options.global_timeout,
+            &mut summary_rows,
+        )
+        .await
+    {
+        return Err(fail_hook(&summary_rows, e));
+    }
+
+    // Each phase is applied tier by tier, then must be ready before the next starts.
+    let phase_count = phases.len();
+    for (index, (phase, members)) in phases.into_iter().enumerate() {
+        if phase_count > 1 {
+            output::info(&format!(
+                "[boom] phase {phase}: applying {} resources",
+                members.len()
+            ));
+        }
+
+        let failed_applies = apply_tiers(&client, catalog, &members, &mut summary_rows).await;
+        applied.extend(members.iter().cloned());
+        if failed_applies > 0 {
+            output::summary_table(&summary_rows);
+            output::finished("failed");
+            return Err(BoomError::Apply(format!(
+                "{failed_applies} resources failed to apply"
+            )));
+        }
+
+        let last = index + 1 == phase_count;
+        if options.verify_result && !last {
+            let state =
+                monitor::watch_resources(&client, catalog, &members, options.global_timeout).await;
+            if state != ResourceState::Ready {
+                output::warn(&format!("[boom] phase {phase} did not become ready"));
+                return Err(fail_unready(
+                    &client,
+                    catalog,
+                    &members,
+                    &applied,
+                    state,
+                    summary_rows,
+                    options,
+                )
+                .await);
+            }
+        }
+    }
+
+    if options.verify_result {
+        let state =
+            monitor::watch_resources(&client, catalog, &resources, options.global_timeout).await;
+        if state != ResourceState::Ready {
+            return Err(fail_unready(
+                &client,
+                catalog,
+                &resources,
+                &applied,
+                state,
+                summary_rows,
+                options,
+            )
+            .await);
+        }
+
+        let total_elapsed = start.elapsed();
+        for row in &mut summary_rows {
+            row.3 = format!("{:.1}s", total_elapsed.as_secs_f64());
+        }
+    }
+
+    if let Err(e) = hooks::run_hooks(
+        &client,
+        catalog,
+        HookKind::PostDeploy,
+        &post_deploy,
+        options.global_timeout,
+        &mut summary_rows,
+    )
+    .await
+    {
+        return Err(fail_hook(&summary_rows, e));
+    }
+
+    output::summary_table(&summary_rows);
+    output::finished("succeeded");
+    Ok(())
+}
+
+// Applies resources in priority-tier order, in parallel within a tier.
+// Returns the number of resources that failed to apply.
+async fn apply_tiers(
+    client: &kube::Client,
+    catalog: &ApiCatalog,
+    resources: &[ResourceDescriptor],
+    summary_rows: &mut Vec<(String, String, String, String)>,
+) -> usize {
+    let mut failed_applies = 0;
 
     for tier in 0..=2u8 {
         let tier_resources: Vec<&ResourceDescriptor> = resources
@@ -34,16 +266,18 @@ pub async fn run(
         let mut set = JoinSet::new();
 
         for resource in tier_resources {
-            let client = client.clone();
             let kind = resource.kind.clone();
             let name = resource.name.clone();
-            let api_version = resource.api_version.clone();
             let raw = resource.raw.clone();
-            let ns = resource.namespace.clone().unwrap_or(namespace.to_string());
+            let ns = resource.namespace.clone().unwrap_or_default();
+            let api = catalog.api(client, &resource.api_version, &kind, &ns);
             let resource_start = Instant::now();
 
             set.spawn(async move {
-                let result = apply_resource(&client, &api_version, &kind, &name, &ns, &raw).await;
+                let result = match api {
+                    Ok(api) => apply_resource(&api, &name, &raw, false).await,
+                    Err(e) => Err(e),
+                };
                 let elapsed = resource_start.elapsed();
                 (kind, name, result, elapsed)
             });
@@ -51,8 +285,8 @@ pub async fn run(
 
         while let Some(join_result) = set.join_next().await {
             match join_result {
-                Ok((kind, name, Ok(()), elapsed)) => {
-                    output::success(&format!("[OK] {kind}/{name}"));
+                Ok((kind, name, Ok(_), elapsed)) => {
+                    output::applied(&kind, &name, None);
                     summary_rows.push((
                         name,
                         kind,
@@ -61,117 +295,105 @@ pub async fn run(
                     ));
                 }
                 Ok((kind, name, Err(err), elapsed)) => {
-                    output::error(&format!("[FAIL] {kind}/{name}: {err}"));
+                    output::applied(&kind, &name, Some(&err));
                     summary_rows.push((
                         name,
                         kind,
                         "Failed".to_string(),
                         format!("{:.1}s", elapsed.as_secs_f64()),
                     ));
-                    all_ok = false;
+                    failed_applies += 1;
                 }
                 Err(err) => {
                     output::error(&format!("[FAIL] join error: {err}"));
-                    all_ok = false;
+                    failed_applies += 1;
                 }
             }
         }
     }
 
-    if !all_ok {
-        output::summary_table(&summary_rows);
-        return false;
+    failed_applies
+}
+
+// Reports diagnostics for the resources that were being waited on, optionally
+// reverts everything applied so far, and prints the summary.
+async fn fail_unready(
+    client: &kube::Client,
+    catalog: &ApiCatalog,
+    waited: &[ResourceDescriptor],
+    applied: &[ResourceDescriptor],
+    state: ResourceState,
+    mut summary_rows: Vec<(String, String, String, String)>,
+    options: &DeployOptions,
+) -> BoomError {
+    let timed_out = state == ResourceState::NotReady;
+    if timed_out {
+        output::warn("[boom] timed out waiting for resources to become ready");
     }
+    for resource in waited {
+        let diag = monitor::collect_diagnostics(client, resource).await;
+        output::diagnostics(&format!("{}/{}", resource.kind, resource.name), &diag);
+    }
+    if options.rollback_on_failure {
+        summary_rows
+            .extend(revert::revert_failed(client, catalog, applied, options.global_timeout).await);
+    }
+    output::summary_table(&summary_rows);
 
-    if !verify_result {
-        output::summary_table(&summary_rows);
-        return true;
+    if timed_out {
+        output::finished("timeout");
+        BoomError::Timeout(format!(
+            "resources not ready after {}s",
+            options.global_timeout
+        ))
+    } else {
+        output::finished("failed");
+        BoomError::Failed("resources failed to become ready".to_string())
     }
+}
 
-    let state = monitor::watch_resources(&client, &resources, global_timeout).await;
-    let total_elapsed = start.elapsed();
-    match state {
-        ResourceState::Ready => {
-            for row in &mut summary_rows {
-                row.3 = format!("{:.1}s", total_elapsed.as_secs_f64());
-            }
-            output::summary_table(&summary_rows);
-            true
-        }
-        ResourceState::Failed => {
-            for resource in &resources {
-                let diag = monitor::collect_diagnostics(&client, resource).await;
-                if !diag.is_empty() {
-                    output::error(&diag);
-                }
-            }
-            output::summary_table(&summary_rows);
-            std::process::exit(1);
-        }
-        ResourceState::NotReady => {
-            output::warn("[boom] timed out waiting for resources to become ready");
-            for resource in &resources {
-                let diag = monitor::collect_diagnostics(&client, resource).await;
-                if !diag.is_empty() {
-                    output::error(&diag);
-                }
+fn fail_hook(summary_rows: &[(String, String, String, String)], error: BoomError) -> BoomError {
+    output::summary_table(summary_rows);
+    output::finished(if matches!(error, BoomError::Timeout(_)) {
+        "timeout"
+    } else {
+        "failed"
+    });
+    error
+}
+
+// Namespaced resources without an explicit namespace land in the target namespace;
+// cluster-scoped resources never carry one.
+pub fn assign_namespaces(
+    catalog: &ApiCatalog,
+    resources: &mut [ResourceDescriptor],
+    namespace: &str,
+) {
+    for resource in resources {
+        match catalog.is_namespaced(&resource.api_version, &resource.kind) {
+            Some(true) if resource.namespace.is_none() => {
+                resource.namespace = Some(namespace.to_string());
             }
-            output::summary_table(&summary_rows);
-            std::process::exit(70);
+            Some(false) => resource.namespace = None,
+            _ => {}
         }
     }
 }
 
-async fn apply_resource(
-    client: &kube::Client,
-    api_version: &str,
-    kind: &str,
+pub async fn apply_resource(
+    api: &Api<DynamicObject>,
     name: &str,
-    namespace: &str,
     raw: &serde_yaml::Value,
-) -> Result<(), String> {
-    let (group, version) = parse_api_version(api_version);
-
-    let ar = ApiResource {
-        group: group.to_string(),
-        version: version.to_string(),
-        api_version: api_version.to_string(),
-        kind: kind.to_string(),
-        plural: pluralize(kind),
-    };
-
-    let api: Api<DynamicObject> =
-        if kind == "Namespace" || kind == "CustomResourceDefinition" || kind == "StorageClass" {
-            Api::all_with(client.clone(), &ar)
-        } else {
-            Api::namespaced_with(client.clone(), namespace, &ar)
-        };
-
+    dry_run: bool,
+) -> Result<DynamicObject, String> {
     let json_value =
         serde_json::to_value(raw).map_err(|e| format!("failed to convert to JSON: {e}"))?;
 
-    let params = PatchParams::apply("boom");
+    let mut params = PatchParams::apply("boom");
+    if dry_run {
+        params = params.dry_run();
+    }
     api.patch(name, &params, &Patch::Apply(json_value))
         .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(())
-}
-
-pub fn parse_api_version(api_version: &str) -> (&str, &str) {
-    match api_version.rsplit_once('/') {
-        Some((group, version)) => (group, version),
-        None => ("", api_version),
-    }
-}
-
-pub fn pluralize(kind: &str) -> String {
-    let lower = kind.to_lowercase();
-    if lower.ends_with('s') {
-        format!("{lower}es")
-    } else if lower.ends_with("cy") {
-        format!("{}ies", &lower[..lower.len() - 1])
-    } else {
-        format!("{lower}s")
-    }
+        .map_err(|e| e.to_string())
 }
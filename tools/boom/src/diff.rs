@@ -0,0 +1,105 @@
+use similar::TextDiff;
+
+use crate::deploy;
+use crate::discovery::ApiCatalog;
+use crate::manifest::ResourceDescriptor;
+use crate::output;
+use crate::secrets;
+
+const NOISY_METADATA: &[&str] = &[
+    "managedFields",
+    "resourceVersion",
+    "uid",
+    "generation",
+    "creationTimestamp",
+    "selfLink",
+];
+
+const NOISY_ANNOTATIONS: &[&str] = &[
+    "kubectl.kubernetes.io/last-applied-configuration",
+    "deployment.kubernetes.io/revision",
+];
+
+pub fn strip_noise(value: &mut serde_json::Value) {
+    let Some(obj) = value.as_object_mut() else {
+        return;
+    };
+    obj.remove("status");
+
+    let Some(metadata) = obj.get_mut("metadata").and_then(|m| m.as_object_mut()) else {
+        return;
+    };
+    for key in NOISY_METADATA {
+        metadata.remove(*key);
+    }
+
+    if let Some(annotations) = metadata
+        .get_mut("annotations")
+        .and_then(|a| a.as_object_mut())
+    {
+        for key in NOISY_ANNOTATIONS {
+            annotations.remove(*key);
+        }
+        if annotations.is_empty() {
+            metadata.remove("annotations");
+        }
+    }
+}
+
+pub fn to_yaml(value: &serde_json::Value) -> String {
+    if value.is_null() {
+        return String::new();
+    }
+    serde_yaml::to_string(value).unwrap_or_default()
+}
+
+pub fn unified_diff(live: &str, desired: &str, label: &str) -> String {
+    TextDiff::from_lines(live, desired)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("live/{label}"), &format!("desired/{label}"))
+        .to_string()
+}
+
+pub async fn run(
+    client: kube::Client,
+    catalog: &ApiCatalog,
+    namespace: &str,
+    resources: Vec<ResourceDescriptor>,
+    secrets: &[String],
+) -> Result<usize, String> {
+    // Hooks are recreated on every deploy rather than applied over the live
+    // object, so there's nothing meaningful to diff for them; canary copies
+    // are temporary and never diffed either.
+    let resources = deploy::prepare(catalog, namespace, resources)?.resources;
+
+    let mut changed = 0;
+
+    for resource in &resources {
+        let ns = resource.namespace.as_deref().unwrap_or_default();
+        let label = format!("{}/{}", resource.kind, resource.name);
+        let api = catalog.api(&client, &resource.api_version, &resource.kind, ns)?;
+
+        let mut live = match api.get_opt(&resource.name).await {
+            Ok(Some(obj)) => serde_json::to_value(&obj).unwrap_or_default(),
+            Ok(None) => serde_json::Value::Null,
+            Err(e) => return Err(format!("failed to get {label}: {e}")),
+        };
+
+        let dry_run = deploy::apply_resource(&api, &resource.name, &resource.raw, true)
+            .await
+            .map_err(|e| format!("dry-run apply failed for {label}: {e}"))?;
+        let mut desired = serde_json::to_value(&dry_run).unwrap_or_default();
+
+        strip_noise(&mut live);
+        strip_noise(&mut desired);
+
+        let diff = unified_diff(&to_yaml(&live), &to_yaml(&desired), &label);
+        if !diff.is_empty() {
+            changed += 1;
+            output::diff(&secrets::redact(&diff, secrets));
+        }
+    }
+
+    Ok(changed)
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use futures::future::join_all;
+use kube::Api;
+use kube::api::{ApiResource, DynamicObject};
+use kube::core::GroupVersion;
+use kube::discovery::{self, Scope};
+
+use crate::manifest::ResourceDescriptor;
+use crate::output;
+
+#[derive(Debug, Clone)]
+pub struct ResourceType {
+    pub resource: ApiResource,
+    pub namespaced: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ApiCatalog {
+    types: HashMap<(String, String), ResourceType>,
+}
+
+impl ApiCatalog {
+    // Each group version is queried on its own: one unhealthy aggregated API
+    // (e.g. metrics.k8s.io answering 503) only loses that group's kinds.
+    pub async fn discover(client: &kube::Client) -> Result<Self, String> {
+        let core = client
+            .list_core_api_versions()
+            .await
+            .map_err(|e| format!("API discovery failed: {e}"))?;
+        let groups = client
+            .list_api_groups()
+            .await
+            .map_err(|e| format!("API discovery failed: {e}"))?;
+        let versions = core.versions.iter().map(|v| GroupVersion::gv("", v)).chain(
+            groups.groups.iter().flat_map(|g| {
+                g.versions
+                    .iter()
+                    .map(|v| GroupVersion::gv(&g.name, &v.version))
+            }),
+        );
+
+        let queries = versions.map(|gv| async move {
+            let result = discovery::pinned_group(client, &gv).await;
+            (gv, result)
+        });
+        let mut catalog = Self::default();
+        for (gv, result) in join_all(queries).await {
+            match result {
+                Ok(group) => {
+                    for (ar, caps) in group.versioned_resources(&gv.version) {
+                        catalog.insert(ar, caps.scope == Scope::Namespaced);
+                    }
+                }
+                Err(e) => output::warn(&format!(
+                    "[boom] skipping API {}: discovery failed: {e}",
+                    gv.api_version()
+                )),
+            }
+        }
+        Ok(catalog)
+    }
+
+    pub fn insert(&mut self, resource: ApiResource, namespaced: bool) {
+        let key = (resource.api_version.clone(), resource.kind.clone());
+        self.types.insert(
+            key,
+            ResourceType {
+                resource,
+                namespaced,
+            },
+        );
+    }
+
+    // CRDs applied in the same run aren't discoverable yet, so read their
+    // plural and scope straight from the manifests.
+    pub fn register_crds(&mut self, resources: &[ResourceDescriptor]) {
+        for crd in resources
+            .iter()
+            .filter(|r| r.kind == "CustomResourceDefinition")
+        {
+            let spec = &crd.raw["spec"];
+            let (Some(group), Some(kind), Some(plural)) = (
+                spec["group"].as_str(),
+                spec["names"]["kind"].as_str(),
+                spec["names"]["plural"].as_str(),
+            ) else {
+                continue;
+            };
+            let namespaced = spec["scope"].as_str() != Some("Cluster");
+            let versions = spec["versions"].as_sequence().cloned().unwrap_or_default();
+
+            for version in versions.iter().filter_map(|v| v["name"].as_str()) {
+                let api_version = format!("{group}/{version}");
+                if self.resolve(&api_version, kind).is_some() {
+                    continue;
+                }
+                self.insert(
+                    ApiResource {
+                        group: group.to_string(),
+                        version: version.to_string(),
+                        api_version,
+                        kind: kind.to_string(),
+                        plural: plural.to_string(),
+                    },
+                    namespaced,
+                );
+            }
+        }
+    }
+
+    pub fn resolve(&self, api_version: &str, kind: &str) -> Option<&ResourceType> {
+        self.types.get(&(api_version.to_string(), kind.to_string()))
+    }
+
+    pub fn is_namespaced(&self, api_version: &str, kind: &str) -> Option<bool> {
+        self.resolve(api_version, kind).map(|t| t.namespaced)
+    }
+
+    pub fn api(
+        &self,
+        client: &kube::Client,
+        api_version: &str,
+        kind: &str,
+        namespace: &str,
+    ) -> Result<Api<DynamicObject>, String> {
+        let ty = self
+            .resolve(api_version, kind)
+            .ok_or_else(|| format!("unknown resource type {api_version}/{kind}"))?;
+        if ty.namespaced {
+            Ok(Api::namespaced_with(
+                client.clone(),
+                namespace,
+                &ty.resource,
+            ))
+        } else {
+            Ok(Api::all_with(client.clone(), &ty.resource))
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoomError {
+    // Invalid invocation: malformed bindings, unknown revisions.
+    Usage(String),
+    // Templates, values, secrets or manifests that can't be loaded or rendered.
+    Render(String),
+    // Kubeconfig, client construction or API discovery.
+    Auth(String),
+    // A resource couldn't be applied, patched, pruned or read back.
+    Apply(String),
+    // A resource reached a terminal failure state.
+    Failed(String),
+    // Resources weren't ready before the global timeout.
+    Timeout(String),
+}
+
+impl BoomError {
+    // 1 and 70 predate the enum and stay stable for existing CI scripts.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Failed(_) => 1,
+            Self::Usage(_) => 64,
+            Self::Render(_) => 65,
+            Self::Apply(_) => 69,
+            Self::Timeout(_) => 70,
+            Self::Auth(_) => 77,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Usage(_) => "usage",
+            Self::Render(_) => "render",
+            Self::Auth(_) => "auth",
+            Self::Apply(_) => "apply",
+            Self::Failed(_) => "failed",
+            Self::Timeout(_) => "timeout",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Usage(msg)
+            | Self::Render(msg)
+            | Self::Auth(msg)
+            | Self::Apply(msg)
+            | Self::Failed(msg)
+            | Self::Timeout(msg) => msg,
+        }
+    }
+
+    // Same kind, message prefixed with where it happened (e.g. a cluster context).
+    #[must_use]
+    pub fn with_context(self, context: &str) -> Self {
+        match self {
+            Self::Usage(msg) => Self::Usage(format!("{context}: {msg}")),
+            Self::Render(msg) => Self::Render(format!("{context}: {msg}")),
+            Self::Auth(msg) => Self::Auth(format!("{context}: {msg}")),
+            Self::Apply(msg) => Self::Apply(format!("{context}: {msg}")),
+            Self::Failed(msg) => Self::Failed(format!("{context}: {msg}")),
+            Self::Timeout(msg) => Self::Timeout(format!("{context}: {msg}")),
+        }
+    }
+}
+
+impl fmt::Display for BoomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for BoomError {}
@@ -0,0 +1,86 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use minijinja::value::Value;
+use minijinja::{Environment, Error, ErrorKind};
+use sha2::{Digest, Sha256};
+
+pub fn register(env: &mut Environment<'_>, allow_env: bool) {
+    env.add_filter("b64encode", b64encode);
+    env.add_filter("b64decode", b64decode);
+    env.add_filter("sha256", sha256);
+    env.add_filter("to_yaml", to_yaml);
+    env.add_filter("to_json", to_json);
+    env.add_filter("nindent", nindent);
+    env.add_filter("quote", quote);
+    env.add_filter("required", required);
+    env.add_function("env", move |name: String, fallback: Option<String>| {
+        lookup_env(allow_env, &name, fallback)
+    });
+}
+
+fn invalid(msg: impl Into<String>) -> Error {
+    Error::new(ErrorKind::InvalidOperation, msg.into())
+}
+
+fn b64encode(value: String) -> String {
+    STANDARD.encode(value)
+}
+
+fn b64decode(value: &str) -> Result<String, Error> {
+    let bytes = STANDARD
+        .decode(value.trim())
+        .map_err(|e| invalid(format!("b64decode: {e}")))?;
+    String::from_utf8(bytes).map_err(|e| invalid(format!("b64decode: {e}")))
+}
+
+fn sha256(value: &Value) -> String {
+    format!("{:x}", Sha256::digest(value.to_string()))
+}
+
+fn to_yaml(value: &Value) -> Result<String, Error> {
+    serde_yaml::to_string(value)
+        .map(|s| s.trim_end().to_string())
+        .map_err(|e| invalid(format!("to_yaml: {e}")))
+}
+
+fn to_json(value: &Value) -> Result<String, Error> {
+    serde_json::to_string(value).map_err(|e| invalid(format!("to_json: {e}")))
+}
+
+// Helm's nindent: a newline, then every line indented, including the first.
+// The builtin `indent` leaves the first line alone unless passed `true`.
+fn nindent(value: &str, width: usize) -> String {
+    let pad = " ".repeat(width);
+    format!("\n{pad}{}", value.replace('\n', &format!("\n{pad}")))
+}
+
+fn quote(value: &Value) -> String {
+    if value.is_undefined() || value.is_none() {
+        return "\"\"".to_string();
+    }
+    serde_json::Value::String(value.to_string()).to_string()
+}
+
+fn required(value: Value, msg: Option<String>) -> Result<Value, Error> {
+    let missing = value.is_undefined() || value.is_none() || value.as_str() == Some("");
+    if missing {
+        return Err(invalid(
+            msg.unwrap_or_else(|| "required value is missing".to_string()),
+        ));
+    }
+    Ok(value)
+}
+
+fn lookup_env(allow_env: bool, name: &str, fallback: Option<String>) -> Result<String, Error> {
+    if !allow_env {
+        return Err(invalid(format!(
+            "env(\"{name}\") is disabled; pass --allow-env to enable it"
+        )));
+    }
+    match std::env::var(name) {
+        Ok(value) => Ok(value),
+        Err(_) => {
+            fallback.ok_or_else(|| invalid(format!("environment variable {name} is not set")))
+        }
+    }
+}
@@ -1,34 +1,32 @@
-use std::process;
-
-use crate::deploy;
+use crate::deploy::{self, DeployOptions};
+use crate::discovery::ApiCatalog;
+use crate::error::BoomError;
 use crate::manifest::{self, ResourceDescriptor};
 
+pub fn namespaced_resources(catalog: &ApiCatalog, resources: &[ResourceDescriptor]) -> Vec<String> {
+    resources
+        .iter()
+        .filter(|r| catalog.is_namespaced(&r.api_version, &r.kind) != Some(false))
+        .map(|r| format!("{}/{}", r.kind, r.name))
+        .collect()
+}
+
 pub async fn run(
     client: kube::Client,
+    catalog: &ApiCatalog,
     mut resources: Vec<ResourceDescriptor>,
-    verify_result: bool,
-    global_timeout: u64,
-) {
-    let namespaced: Vec<_> = resources
-        .iter()
-        .filter(|r| !manifest::is_cluster_scoped(&r.kind))
-        .map(|r| format!("{}/{}", r.kind, r.name))
-        .collect();
+    options: &DeployOptions,
+) -> Result<(), BoomError> {
+    let namespaced = namespaced_resources(catalog, &resources);
 
     if !namespaced.is_empty() {
-        eprintln!(
-            "boom: global-deploy rejects namespaced resources: {}",
+        return Err(BoomError::Render(format!(
+            "global-deploy rejects namespaced resources: {}",
             namespaced.join(", ")
-        );
-        process::exit(1);
+        )));
     }
 
     manifest::sort_by_priority(&mut resources);
 
-    let ok = deploy::run(client, "", resources, verify_result, global_timeout).await;
-    if ok {
-        process::exit(0);
-    } else {
-        process::exit(1);
-    }
+    deploy::run(client, catalog, "", resources, options).await
 }
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use crate::manifest::ResourceDescriptor;
+
+pub const TIMEOUT_ANNOTATION: &str = "boom.io/timeout";
+pub const SKIP_VERIFY_ANNOTATION: &str = "boom.io/skip-verify";
+pub const REQUIRED_ROLLOUT_ANNOTATION: &str = "boom.io/required-rollout";
+
+// How much of a Deployment rollout must finish before it counts as ready.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RequiredRollout {
+    // Every replica updated and available.
+    #[default]
+    Full,
+    // Ready as soon as the apply succeeds.
+    None,
+    // Every replica updated, with at most the strategy's maxUnavailable missing.
+    MaxUnavailable,
+    // At least this percentage of desired replicas updated and available.
+    Percent(u8),
+}
+
+impl RequiredRollout {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "full" => Ok(Self::Full),
+            "none" => Ok(Self::None),
+            "maxUnavailable" => Ok(Self::MaxUnavailable),
+            _ => value
+                .strip_suffix('%')
+                .and_then(|p| p.parse::<u8>().ok())
+                .filter(|p| *p <= 100)
+                .map(Self::Percent)
+                .ok_or_else(|| {
+                    format!(
+                        "invalid {REQUIRED_ROLLOUT_ANNOTATION} {value:?} (expected full, none, maxUnavailable or a percentage)"
+                    )
+                }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResourceHints {
+    pub timeout: Option<Duration>,
+    pub skip_verify: bool,
+}
+
+// Accepts plain seconds or a single s/m/h suffix: "900", "900s", "15m", "1h".
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let (digits, unit) = match value.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&value[..i], c),
+        _ => (value, 's'),
+    };
+    let amount: u64 = digits
+        .parse()
+        .map_err(|_| format!("invalid duration {value:?}"))?;
+    let seconds = match unit {
+        's' => Some(amount),
+        'm' => amount.checked_mul(60),
+        'h' => amount.checked_mul(3600),
+        _ => None,
+    }
+    .ok_or_else(|| format!("invalid duration {value:?}"))?;
+    Ok(Duration::from_secs(seconds))
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, String> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!(
+            "invalid {key} {value:?} (expected \"true\" or \"false\")"
+        )),
+    }
+}
+
+pub fn annotation<'a>(resource: &'a ResourceDescriptor, key: &str) -> Option<&'a str> {
+    resource
+        .raw
+        .get("metadata")?
+        .get("annotations")?
+        .get(key)?
+        .as_str()
+}
+
+pub fn resource_hints(resource: &ResourceDescriptor) -> Result<ResourceHints, String> {
+    let context = |e: String| format!("{}/{}: {e}", resource.kind, resource.name);
+
+    let timeout = annotation(resource, TIMEOUT_ANNOTATION)
+        .map(parse_duration)
+        .transpose()
+        .map_err(|e| context(format!("{TIMEOUT_ANNOTATION}: {e}")))?;
+    let skip_verify = annotation(resource, SKIP_VERIFY_ANNOTATION)
+        .map(|v| parse_bool(SKIP_VERIFY_ANNOTATION, v))
+        .transpose()
+        .map_err(context)?
+        .unwrap_or(false);
+    // Only validated here, so a bad value fails the deploy before anything is
+    // applied; the readiness check reads it off the live Deployment.
+    if let Some(value) = annotation(resource, REQUIRED_ROLLOUT_ANNOTATION) {
+        RequiredRollout::parse(value).map_err(context)?;
+    }
+
+    Ok(ResourceHints {
+        timeout,
+        skip_verify,
+    })
+}
@@ -0,0 +1,193 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::Api;
+use kube::api::{DeleteParams, ListParams, PostParams};
+
+use crate::manifest::{self, ResourceDescriptor};
+
+// Kept separate from the ownership label so pruning never sees history Secrets.
+pub const HISTORY_LABEL: &str = "boom.io/history-of";
+pub const REVISION_LABEL: &str = "boom.io/revision";
+// "succeeded" or "failed"; revisions recorded before the label existed succeeded.
+pub const STATUS_LABEL: &str = "boom.io/status";
+pub const HISTORY_LIMIT: usize = 10;
+
+const MANIFESTS_KEY: &str = "manifests";
+
+pub fn revision_name(owner: &str, revision: u32) -> String {
+    let owner = owner.to_lowercase().replace('_', "-");
+    format!("boom-history-{owner}-v{revision}")
+}
+
+pub fn serialize_manifests(resources: &[ResourceDescriptor]) -> Result<String, String> {
+    let documents = resources
+        .iter()
+        .map(|r| serde_yaml::to_string(&r.raw))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("failed to serialize manifests: {e}"))?;
+    Ok(documents.join("---\n"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Revision {
+    pub number: u32,
+    pub succeeded: bool,
+}
+
+pub fn revision_secret(owner: &str, revision: Revision, manifests: &str) -> Secret {
+    let status = if revision.succeeded {
+        "succeeded"
+    } else {
+        "failed"
+    };
+    let labels = BTreeMap::from([
+        (HISTORY_LABEL.to_string(), owner.to_string()),
+        (REVISION_LABEL.to_string(), revision.number.to_string()),
+        (STATUS_LABEL.to_string(), status.to_string()),
+    ]);
+    Secret {
+        metadata: ObjectMeta {
+            name: Some(revision_name(owner, revision.number)),
+            labels: Some(labels),
+            ..Default::default()
+        },
+        type_: Some("boom.io/history".to_string()),
+        string_data: Some(BTreeMap::from([(
+            MANIFESTS_KEY.to_string(),
+            manifests.to_string(),
+        )])),
+        ..Default::default()
+    }
+}
+
+// With no explicit target, roll back to the newest successful revision other
+// than the newest one: after a failed deploy that is the last good state, and
+// after a successful one it is the state before it.
+pub fn rollback_target(revisions: &[Revision], requested: Option<u32>) -> Result<u32, String> {
+    let mut sorted = revisions.to_vec();
+    sorted.sort_unstable_by_key(|r| r.number);
+
+    match requested {
+        Some(revision) if sorted.iter().any(|r| r.number == revision) => Ok(revision),
+        Some(revision) => Err(format!("revision {revision} not found")),
+        None => sorted
+            .split_last()
+            .and_then(|(_, earlier)| earlier.iter().rev().find(|r| r.succeeded))
+            .map(|r| r.number)
+            .ok_or_else(|| "no previous successful revision to roll back to".to_string()),
+    }
+}
+
+// `rollback` without --owner restores the namespace's only recorded deploy.
+pub fn only_owner(owners: &BTreeSet<String>) -> Result<String, String> {
+    let mut iter = owners.iter();
+    match (iter.next(), iter.next()) {
+        (Some(owner), None) => Ok(owner.clone()),
+        (None, _) => Err("no deploy history in this namespace".to_string()),
+        (Some(_), Some(_)) => Err(format!(
+            "several deploys recorded in this namespace, pass --owner: {}",
+            owners.iter().cloned().collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+pub async fn list_owners(
+    client: &kube::Client,
+    namespace: &str,
+) -> Result<BTreeSet<String>, String> {
+    let api: Api<Secret> = Api::namespaced(client.clone(), namespace);
+    let list = api
+        .list(&ListParams::default().labels(HISTORY_LABEL))
+        .await
+        .map_err(|e| format!("failed to list deploy history: {e}"))?;
+    Ok(list
+        .items
+        .iter()
+        .filter_map(|s| s.metadata.labels.as_ref()?.get(HISTORY_LABEL).cloned())
+        .collect())
+}
+
+pub async fn list_revisions(
+    client: &kube::Client,
+    namespace: &str,
+    owner: &str,
+) -> Result<Vec<Revision>, String> {
+    let api: Api<Secret> = Api::namespaced(client.clone(), namespace);
+    let lp = ListParams::default().labels(&format!("{HISTORY_LABEL}={owner}"));
+    let list = api
+        .list(&lp)
+        .await
+        .map_err(|e| format!("failed to list deploy history: {e}"))?;
+
+    let mut revisions: Vec<Revision> = list
+        .items
+        .iter()
+        .filter_map(|s| {
+            let labels = s.metadata.labels.as_ref()?;
+            Some(Revision {
+                number: labels.get(REVISION_LABEL)?.parse().ok()?,
+                succeeded: labels.get(STATUS_LABEL).is_none_or(|s| s != "failed"),
+            })
+        })
+        .collect();
+    revisions.sort_unstable_by_key(|r| r.number);
+    Ok(revisions)
+}
+
+pub async fn record(
+    client: &kube::Client,
+    namespace: &str,
+    owner: &str,
+    resources: &[ResourceDescriptor],
+    succeeded: bool,
+) -> Result<u32, String> {
+    let revisions = list_revisions(client, namespace, owner).await?;
+    let revision = Revision {
+        number: revisions.last().map_or(1, |latest| latest.number + 1),
+        succeeded,
+    };
+    let manifests = serialize_manifests(resources)?;
+
+    let api: Api<Secret> = Api::namespaced(client.clone(), namespace);
+    api.create(
+        &PostParams::default(),
+        &revision_secret(owner, revision, &manifests),
+    )
+    .await
+    .map_err(|e| format!("failed to record revision {}: {e}", revision.number))?;
+
+    let expired = (revisions.len() + 1).saturating_sub(HISTORY_LIMIT);
+    for old in revisions.iter().take(expired) {
+        api.delete(&revision_name(owner, old.number), &DeleteParams::default())
+            .await
+            .map_err(|e| format!("failed to delete revision {}: {e}", old.number))?;
+    }
+
+    Ok(revision.number)
+}
+
+pub async fn load(
+    client: &kube::Client,
+    namespace: &str,
+    owner: &str,
+    revision: u32,
+) -> Result<Vec<ResourceDescriptor>, String> {
+    let name = revision_name(owner, revision);
+    let api: Api<Secret> = Api::namespaced(client.clone(), namespace);
+    let secret = api
+        .get(&name)
+        .await
+        .map_err(|e| format!("failed to read revision {revision}: {e}"))?;
+
+    let bytes = secret
+        .data
+        .as_ref()
+        .and_then(|d| d.get(MANIFESTS_KEY))
+        .ok_or_else(|| format!("revision {revision} has no manifests"))?;
+    let yaml = String::from_utf8(bytes.0.clone())
+        .map_err(|e| format!("revision {revision} is not valid UTF-8: {e}"))?;
+
+    manifest::parse_source(&name, &yaml)
+}
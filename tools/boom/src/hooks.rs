@@ -0,0 +1,263 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use futures::{AsyncBufReadExt, StreamExt};
+use k8s_openapi::api::core::v1::Pod;
+use kube::Api;
+use kube::api::{DeleteParams, DynamicObject, ListParams, LogParams};
+
+use crate::deploy::apply_resource;
+use crate::discovery::ApiCatalog;
+use crate::error::BoomError;
+use crate::hints::annotation;
+use crate::manifest::ResourceDescriptor;
+use crate::monitor::{self, ResourceState};
+use crate::output;
+
+pub const HOOK_ANNOTATION: &str = "boom.io/hook";
+pub const HOOK_DELETE_POLICY_ANNOTATION: &str = "boom.io/hook-delete-policy";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    PreDeploy,
+    PostDeploy,
+}
+
+impl HookKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::PreDeploy => "pre-deploy",
+            Self::PostDeploy => "post-deploy",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeletePolicy {
+    // Delete any previous run before creating the hook; Jobs can't be re-applied
+    // once their pod template is set.
+    BeforeHookCreation,
+    HookSucceeded,
+    HookFailed,
+}
+
+pub fn hook_kind(resource: &ResourceDescriptor) -> Result<Option<HookKind>, String> {
+    let Some(value) = annotation(resource, HOOK_ANNOTATION) else {
+        return Ok(None);
+    };
+    let kind = match value {
+        "pre-deploy" => HookKind::PreDeploy,
+        "post-deploy" => HookKind::PostDeploy,
+        _ => {
+            return Err(format!(
+                "{}/{}: invalid {HOOK_ANNOTATION} {value:?} (expected pre-deploy or post-deploy)",
+                resource.kind, resource.name
+            ));
+        }
+    };
+    if !matches!(resource.kind.as_str(), "Job" | "Pod") {
+        return Err(format!(
+            "{}/{}: only Jobs and Pods can be hooks",
+            resource.kind, resource.name
+        ));
+    }
+    Ok(Some(kind))
+}
+
+pub fn delete_policies(resource: &ResourceDescriptor) -> Result<Vec<DeletePolicy>, String> {
+    let Some(value) = annotation(resource, HOOK_DELETE_POLICY_ANNOTATION) else {
+        return Ok(vec![DeletePolicy::BeforeHookCreation]);
+    };
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|policy| match policy {
+            "before-hook-creation" => Ok(DeletePolicy::BeforeHookCreation),
+            "hook-succeeded" => Ok(DeletePolicy::HookSucceeded),
+            "hook-failed" => Ok(DeletePolicy::HookFailed),
+            _ => Err(format!(
+                "{}/{}: invalid {HOOK_DELETE_POLICY_ANNOTATION} {policy:?}",
+                resource.kind, resource.name
+            )),
+        })
+        .collect()
+}
+
+#[derive(Debug, Default)]
+pub struct SplitHooks {
+    pub pre_deploy: Vec<ResourceDescriptor>,
+    pub resources: Vec<ResourceDescriptor>,
+    pub post_deploy: Vec<ResourceDescriptor>,
+}
+
+// Separates hooks from regular resources, keeping each list in input order.
+pub fn split_hooks(resources: Vec<ResourceDescriptor>) -> Result<SplitHooks, String> {
+    let mut split = SplitHooks::default();
+    for resource in resources {
+        delete_policies(&resource)?;
+        match hook_kind(&resource)? {
+            Some(HookKind::PreDeploy) => split.pre_deploy.push(resource),
+            Some(HookKind::PostDeploy) => split.post_deploy.push(resource),
+            None => split.resources.push(resource),
+        }
+    }
+    Ok(split)
+}
+
+fn hook_api(
+    client: &kube::Client,
+    catalog: &ApiCatalog,
+    hook: &ResourceDescriptor,
+) -> Result<Api<DynamicObject>, BoomError> {
+    let ns = hook.namespace.as_deref().unwrap_or("default");
+    catalog
+        .api(client, &hook.api_version, &hook.kind, ns)
+        .map_err(BoomError::Apply)
+}
+
+async fn delete_hook(api: &Api<DynamicObject>, hook: &ResourceDescriptor) -> Result<(), String> {
+    let key = format!("{}/{}", hook.kind, hook.name);
+    match api.delete(&hook.name, &DeleteParams::background()).await {
+        Ok(_) => {}
+        Err(kube::Error::Api(e)) if e.code == 404 => return Ok(()),
+        Err(e) => return Err(format!("failed to delete hook {key}: {e}")),
+    }
+
+    let deadline = Instant::now() + Duration::from_mins(1);
+    while Instant::now() < deadline {
+        match api.get_opt(&hook.name).await {
+            Ok(None) => return Ok(()),
+            Ok(Some(_)) => tokio::time::sleep(Duration::from_secs(1)).await,
+            Err(e) => return Err(format!("failed to delete hook {key}: {e}")),
+        }
+    }
+    Err(format!("timed out deleting hook {key}"))
+}
+
+// Follows the logs of every pod the hook creates until aborted; pods that
+// haven't started yet are retried on the next pass.
+async fn stream_logs(client: kube::Client, hook: ResourceDescriptor) {
+    let ns = hook.namespace.as_deref().unwrap_or("default");
+    let pods: Api<Pod> = Api::namespaced(client, ns);
+    let key = format!("{}/{}", hook.kind, hook.name);
+    let lp = if hook.kind == "Pod" {
+        ListParams::default().fields(&format!("metadata.name={}", hook.name))
+    } else {
+        ListParams::default().labels(&format!("job-name={}", hook.name))
+    };
+    let params = LogParams {
+        follow: true,
+        ..Default::default()
+    };
+
+    let mut streamed = HashSet::new();
+    loop {
+        let names: Vec<String> = match pods.list(&lp).await {
+            Ok(list) => list
+                .items
+                .into_iter()
+                .filter_map(|p| p.metadata.name)
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        for name in names {
+            if streamed.contains(&name) {
+                continue;
+            }
+            let Ok(stream) = pods.log_stream(&name, &params).await else {
+                continue;
+            };
+            streamed.insert(name);
+            let mut lines = stream.lines();
+            while let Some(Ok(line)) = lines.next().await {
+                output::hook_log(&key, &line);
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+pub async fn run_hook(
+    client: &kube::Client,
+    catalog: &ApiCatalog,
+    hook: &ResourceDescriptor,
+    timeout_secs: u64,
+) -> Result<ResourceState, BoomError> {
+    let api = hook_api(client, catalog, hook)?;
+    let policies = delete_policies(hook).map_err(BoomError::Render)?;
+
+    if policies.contains(&DeletePolicy::BeforeHookCreation) {
+        delete_hook(&api, hook).await.map_err(BoomError::Apply)?;
+    }
+    apply_resource(&api, &hook.name, &hook.raw, false)
+        .await
+        .map_err(|e| BoomError::Apply(format!("{}/{}: {e}", hook.kind, hook.name)))?;
+
+    let logs = tokio::spawn(output::in_current_cluster(stream_logs(
+        client.clone(),
+        hook.clone(),
+    )));
+    let state =
+        monitor::watch_resources(client, catalog, std::slice::from_ref(hook), timeout_secs).await;
+    // Give the log stream a moment to drain the final lines.
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    logs.abort();
+
+    let delete = match state {
+        ResourceState::Ready => policies.contains(&DeletePolicy::HookSucceeded),
+        _ => policies.contains(&DeletePolicy::HookFailed),
+    };
+    if delete && let Err(e) = delete_hook(&api, hook).await {
+        output::warn(&format!("[boom] {e}"));
+    }
+    Ok(state)
+}
+
+// Runs hooks one at a time, stopping at the first that fails or times out.
+pub async fn run_hooks(
+    client: &kube::Client,
+    catalog: &ApiCatalog,
+    kind: HookKind,
+    hooks: &[ResourceDescriptor],
+    timeout_secs: u64,
+    summary_rows: &mut Vec<(String, String, String, String)>,
+) -> Result<(), BoomError> {
+    for hook in hooks {
+        let key = format!("{}/{}", hook.kind, hook.name);
+        output::info(&format!("[boom] running {} hook {key}", kind.label()));
+        let start = Instant::now();
+        let state = run_hook(client, catalog, hook, timeout_secs).await;
+
+        let status = match &state {
+            Ok(ResourceState::Ready) => "Ready",
+            Ok(ResourceState::NotReady) => "Timeout",
+            _ => "Failed",
+        };
+        summary_rows.push((
+            hook.name.clone(),
+            hook.kind.clone(),
+            status.to_string(),
+            format!("{:.1}s", start.elapsed().as_secs_f64()),
+        ));
+
+        match state? {
+            ResourceState::Ready => {}
+            ResourceState::Failed => {
+                let diag = monitor::collect_diagnostics(client, hook).await;
+                output::diagnostics(&key, &diag);
+                return Err(BoomError::Failed(format!(
+                    "{} hook {key} failed",
+                    kind.label()
+                )));
+            }
+            ResourceState::NotReady => {
+                return Err(BoomError::Timeout(format!(
+                    "{} hook {key} did not complete after {timeout_secs}s",
+                    kind.label()
+                )));
+            }
+        }
+    }
+    Ok(())
+}
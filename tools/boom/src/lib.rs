@@ -1,9 +1,25 @@
+pub mod canary;
 pub mod client;
+pub mod clusters;
 pub mod deploy;
+pub mod diff;
+pub mod discovery;
+pub mod error;
+pub mod filters;
 pub mod global_deploy;
+pub mod hints;
+pub mod history;
+pub mod hooks;
+pub mod lint;
 pub mod manifest;
 pub mod monitor;
+pub mod namespaces;
 pub mod output;
+pub mod phases;
 pub mod prune;
 pub mod render;
 pub mod restart;
+pub mod revert;
+pub mod rollback;
+pub mod secrets;
+pub mod validate;
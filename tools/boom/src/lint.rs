@@ -0,0 +1,361 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_yaml::Value;
+
+use crate::error::BoomError;
+use crate::hints::annotation;
+use crate::manifest::ResourceDescriptor;
+use crate::output;
+
+pub const LINT_DISABLE_ANNOTATION: &str = "boom.io/lint-disable";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Off,
+    Warning,
+    Error,
+}
+
+// Rule id, default level, description.
+pub const RULES: &[(&str, Level, &str)] = &[
+    (
+        "missing-resources",
+        Level::Warning,
+        "containers must set resources.requests and resources.limits",
+    ),
+    (
+        "latest-tag",
+        Level::Error,
+        "images must be pinned to a tag other than :latest or a digest",
+    ),
+    (
+        "missing-readiness-probe",
+        Level::Warning,
+        "long-running workload containers must define a readinessProbe",
+    ),
+    (
+        "privileged",
+        Level::Error,
+        "containers must not run privileged",
+    ),
+    (
+        "host-path",
+        Level::Error,
+        "pods must not mount hostPath volumes",
+    ),
+    (
+        "duplicate-resource",
+        Level::Error,
+        "each kind/name may appear only once per namespace",
+    ),
+    (
+        "service-selector",
+        Level::Warning,
+        "Service selectors must match a workload in the same manifests",
+    ),
+];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+    #[serde(default)]
+    pub rules: BTreeMap<String, Level>,
+}
+
+impl LintConfig {
+    pub fn parse(yaml: &str) -> Result<Self, String> {
+        let config: Self =
+            serde_yaml::from_str(yaml).map_err(|e| format!("invalid lint config: {e}"))?;
+        for rule in config.rules.keys() {
+            if !RULES.iter().any(|(id, _, _)| id == rule) {
+                return Err(format!("invalid lint config: unknown rule {rule:?}"));
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read lint config {}: {e}", path.display()))?;
+        Self::parse(&content)
+    }
+
+    pub fn level(&self, rule: &str) -> Level {
+        self.rules.get(rule).copied().unwrap_or_else(|| {
+            RULES
+                .iter()
+                .find(|(id, _, _)| *id == rule)
+                .map_or(Level::Off, |(_, level, _)| *level)
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub rule: &'static str,
+    pub level: Level,
+    pub source: String,
+    pub resource: String,
+    pub message: String,
+}
+
+fn pod_spec(resource: &ResourceDescriptor) -> Option<&Value> {
+    let spec = resource.raw.get("spec")?;
+    match resource.kind.as_str() {
+        "Pod" => Some(spec),
+        "Deployment" | "StatefulSet" | "DaemonSet" | "ReplicaSet" | "Job" => {
+            spec.get("template")?.get("spec")
+        }
+        "CronJob" => spec
+            .get("jobTemplate")?
+            .get("spec")?
+            .get("template")?
+            .get("spec"),
+        _ => None,
+    }
+}
+
+fn pod_labels(resource: &ResourceDescriptor) -> Option<&Value> {
+    match resource.kind.as_str() {
+        "Pod" => resource.raw.get("metadata")?.get("labels"),
+        "Deployment" | "StatefulSet" | "DaemonSet" | "ReplicaSet" => resource
+            .raw
+            .get("spec")?
+            .get("template")?
+            .get("metadata")?
+            .get("labels"),
+        _ => None,
+    }
+}
+
+fn containers<'a>(spec: &'a Value, key: &str) -> impl Iterator<Item = &'a Value> {
+    spec.get(key)
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+}
+
+fn all_containers(spec: &Value) -> impl Iterator<Item = &Value> {
+    containers(spec, "initContainers").chain(containers(spec, "containers"))
+}
+
+fn container_name(container: &Value) -> &str {
+    container
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or("<unnamed>")
+}
+
+// An image without a tag or digest resolves to :latest.
+pub fn is_latest(image: &str) -> bool {
+    if image.contains('@') {
+        return false;
+    }
+    let last_segment = image.rsplit('/').next().unwrap_or(image);
+    match last_segment.rsplit_once(':') {
+        Some((_, tag)) => tag == "latest",
+        None => true,
+    }
+}
+
+fn check_resources(spec: &Value) -> Vec<String> {
+    let mut messages = Vec::new();
+    for container in all_containers(spec) {
+        let resources = container.get("resources");
+        for key in ["requests", "limits"] {
+            let set = resources
+                .and_then(|r| r.get(key))
+                .and_then(Value::as_mapping)
+                .is_some_and(|m| !m.is_empty());
+            if !set {
+                messages.push(format!(
+                    "container {} has no resources.{key}",
+                    container_name(container)
+                ));
+            }
+        }
+    }
+    messages
+}
+
+fn check_latest(spec: &Value) -> Vec<String> {
+    all_containers(spec)
+        .filter_map(|container| {
+            let image = container.get("image").and_then(Value::as_str)?;
+            is_latest(image).then(|| {
+                format!(
+                    "container {} uses unpinned image {image}",
+                    container_name(container)
+                )
+            })
+        })
+        .collect()
+}
+
+fn check_readiness(resource: &ResourceDescriptor, spec: &Value) -> Vec<String> {
+    if !matches!(
+        resource.kind.as_str(),
+        "Deployment" | "StatefulSet" | "DaemonSet" | "ReplicaSet"
+    ) {
+        return Vec::new();
+    }
+    containers(spec, "containers")
+        .filter(|c| c.get("readinessProbe").is_none())
+        .map(|c| format!("container {} has no readinessProbe", container_name(c)))
+        .collect()
+}
+
+fn check_privileged(spec: &Value) -> Vec<String> {
+    all_containers(spec)
+        .filter(|c| {
+            c.get("securityContext")
+                .and_then(|s| s.get("privileged"))
+                .and_then(Value::as_bool)
+                == Some(true)
+        })
+        .map(|c| format!("container {} runs privileged", container_name(c)))
+        .collect()
+}
+
+fn check_host_path(spec: &Value) -> Vec<String> {
+    spec.get("volumes")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter(|v| v.get("hostPath").is_some())
+        .map(|v| {
+            let name = v.get("name").and_then(Value::as_str).unwrap_or("<unnamed>");
+            let path = v["hostPath"]["path"].as_str().unwrap_or_default();
+            format!("volume {name} mounts hostPath {path}")
+        })
+        .collect()
+}
+
+fn selector_matches(selector: &serde_yaml::Mapping, labels: Option<&Value>) -> bool {
+    let Some(labels) = labels.and_then(Value::as_mapping) else {
+        return false;
+    };
+    selector.iter().all(|(k, v)| labels.get(k) == Some(v))
+}
+
+fn check_service_selector(
+    service: &ResourceDescriptor,
+    resources: &[ResourceDescriptor],
+) -> Vec<String> {
+    let spec = &service.raw["spec"];
+    if spec["type"].as_str() == Some("ExternalName") {
+        return Vec::new();
+    }
+    let Some(selector) = spec["selector"].as_mapping().filter(|s| !s.is_empty()) else {
+        return Vec::new();
+    };
+    let matched = resources
+        .iter()
+        .filter(|r| r.namespace == service.namespace)
+        .any(|r| selector_matches(selector, pod_labels(r)));
+    if matched {
+        return Vec::new();
+    }
+    let selector: Vec<String> = selector
+        .iter()
+        .map(|(k, v)| {
+            format!(
+                "{}={}",
+                k.as_str().unwrap_or_default(),
+                v.as_str().unwrap_or_default()
+            )
+        })
+        .collect();
+    vec![format!(
+        "selector {} matches no workload in these manifests",
+        selector.join(",")
+    )]
+}
+
+fn disabled_rules(resource: &ResourceDescriptor) -> HashSet<&str> {
+    annotation(resource, LINT_DISABLE_ANNOTATION)
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|r| !r.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn lint(resources: &[ResourceDescriptor], config: &LintConfig) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut seen = HashSet::new();
+
+    for resource in resources {
+        let disabled = disabled_rules(resource);
+        let mut report = |rule: &'static str, messages: Vec<String>| {
+            let level = config.level(rule);
+            if level == Level::Off || disabled.contains(rule) {
+                return;
+            }
+            findings.extend(messages.into_iter().map(|message| Finding {
+                rule,
+                level,
+                source: resource.source.clone(),
+                resource: format!("{}/{}", resource.kind, resource.name),
+                message,
+            }));
+        };
+
+        if let Some(spec) = pod_spec(resource) {
+            report("missing-resources", check_resources(spec));
+            report("latest-tag", check_latest(spec));
+            report("missing-readiness-probe", check_readiness(resource, spec));
+            report("privileged", check_privileged(spec));
+            report("host-path", check_host_path(spec));
+        }
+        if resource.kind == "Service" {
+            report(
+                "service-selector",
+                check_service_selector(resource, resources),
+            );
+        }
+
+        let key = (&resource.kind, &resource.namespace, &resource.name);
+        if !seen.insert(key) {
+            report(
+                "duplicate-resource",
+                vec![format!(
+                    "{}/{} is defined more than once",
+                    resource.kind, resource.name
+                )],
+            );
+        }
+    }
+    findings
+}
+
+pub fn report(findings: &[Finding]) -> Result<(), BoomError> {
+    for finding in findings {
+        let line = format!(
+            "{}: {}: {} ({})",
+            finding.source, finding.resource, finding.message, finding.rule
+        );
+        match finding.level {
+            Level::Error => output::error(&format!("[ERROR] {line}")),
+            _ => output::warn(&format!("[WARN] {line}")),
+        }
+    }
+    let errors = findings.iter().filter(|f| f.level == Level::Error).count();
+    if errors > 0 {
+        return Err(BoomError::Render(format!(
+            "{errors} lint errors, {} warnings",
+            findings.len() - errors
+        )));
+    }
+    output::success(&format!(
+        "[boom] lint passed with {} warnings",
+        findings.len()
+    ));
+    Ok(())
+}
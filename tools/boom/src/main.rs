@@ -1,10 +1,10 @@
 use std::path::{Path, PathBuf};
 use std::process;
 
+use boom::error::BoomError;
+use boom::output::OutputFormat;
 use clap::{Parser, Subcommand};
 
-mod client;
-
 #[derive(Parser)]
 #[command(name = "boom", about = "Kubernetes deploy tool")]
 struct Cli {
@@ -15,21 +15,94 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     Deploy(DeployArgs),
+    Diff(DiffArgs),
     GlobalDeploy(GlobalDeployArgs),
     Restart(RestartArgs),
+    Rollback(RollbackArgs),
     Render(RenderArgs),
+    Validate(ValidateArgs),
+    Lint(LintArgs),
 }
 
-#[derive(Parser)]
-struct DeployArgs {
-    #[arg(short, long)]
-    namespace: String,
+#[derive(clap::Args)]
+struct TemplateArgs {
     #[arg(long)]
     dir: PathBuf,
     #[arg(long)]
-    values: Option<PathBuf>,
+    values: Vec<PathBuf>,
     #[arg(long)]
-    context: Option<String>,
+    bindings: Vec<String>,
+    #[arg(long)]
+    allow_env: bool,
+    #[arg(long)]
+    secrets: Option<PathBuf>,
+    #[arg(long)]
+    secrets_key: Option<PathBuf>,
+    #[arg(long)]
+    owner: Option<String>,
+}
+
+impl TemplateArgs {
+    fn owner(&self) -> Result<String, BoomError> {
+        let owner = match &self.owner {
+            Some(owner) => owner.clone(),
+            None => boom::manifest::owner_from_dir(&self.dir).map_err(BoomError::Usage)?,
+        };
+        // An empty owner label would match every unlabeled object when pruning.
+        if owner.is_empty() {
+            return Err(BoomError::Usage("--owner must not be empty".to_string()));
+        }
+        Ok(owner)
+    }
+}
+
+#[derive(clap::Args)]
+struct ClusterArgs {
+    #[arg(long)]
+    contexts_file: Option<PathBuf>,
+    #[arg(long)]
+    parallel: bool,
+    #[arg(long, conflicts_with = "parallel")]
+    fail_fast: bool,
+}
+
+impl ClusterArgs {
+    fn targets(
+        &self,
+        contexts: &[String],
+    ) -> Result<Vec<boom::clusters::ClusterTarget>, BoomError> {
+        let file = match &self.contexts_file {
+            Some(path) => boom::clusters::load_contexts_file(path).map_err(BoomError::Usage)?,
+            None => Vec::new(),
+        };
+        boom::clusters::targets(contexts, file).map_err(BoomError::Usage)
+    }
+
+    fn mode(&self) -> boom::clusters::FanOut {
+        if self.parallel {
+            boom::clusters::FanOut::Parallel
+        } else {
+            boom::clusters::FanOut::Sequential {
+                fail_fast: self.fail_fast,
+            }
+        }
+    }
+}
+
+#[derive(Parser)]
+struct DeployArgs {
+    #[arg(short, long, required_unless_present = "multi_namespace")]
+    namespace: Option<String>,
+    #[arg(long, requires = "allow_namespaces")]
+    multi_namespace: bool,
+    #[arg(long, value_delimiter = ',')]
+    allow_namespaces: Vec<String>,
+    #[command(flatten)]
+    template: TemplateArgs,
+    #[arg(long)]
+    context: Vec<String>,
+    #[command(flatten)]
+    clusters: ClusterArgs,
     #[arg(long)]
     selector: Option<String>,
     #[arg(long, default_value_t = 300)]
@@ -38,16 +111,34 @@ struct DeployArgs {
     verify_result: bool,
     #[arg(long)]
     prune: bool,
+    #[arg(long)]
+    rollback_on_failure: bool,
+    #[arg(long)]
+    canary: bool,
+    #[arg(long)]
+    skip_validation: bool,
+    #[arg(long, value_enum, default_value_t)]
+    output: OutputFormat,
 }
 
 #[derive(Parser)]
-struct GlobalDeployArgs {
-    #[arg(long)]
-    dir: PathBuf,
-    #[arg(long)]
-    values: Option<PathBuf>,
+struct DiffArgs {
+    #[arg(short, long)]
+    namespace: String,
+    #[command(flatten)]
+    template: TemplateArgs,
     #[arg(long)]
     context: Option<String>,
+}
+
+#[derive(Parser)]
+struct GlobalDeployArgs {
+    #[command(flatten)]
+    template: TemplateArgs,
+    #[arg(long)]
+    context: Vec<String>,
+    #[command(flatten)]
+    clusters: ClusterArgs,
     #[arg(long)]
     selector: Option<String>,
     #[arg(long, default_value_t = 300)]
@@ -56,6 +147,10 @@ struct GlobalDeployArgs {
     verify_result: bool,
     #[arg(long)]
     prune: bool,
+    #[arg(long)]
+    skip_validation: bool,
+    #[arg(long, value_enum, default_value_t)]
+    output: OutputFormat,
 }
 
 #[derive(Parser)]
@@ -74,6 +169,24 @@ struct RestartArgs {
     global_timeout: u64,
     #[arg(long, default_value_t = true)]
     verify_result: bool,
+    #[arg(long, value_enum, default_value_t)]
+    output: OutputFormat,
+}
+
+#[derive(Parser)]
+struct RollbackArgs {
+    #[arg(short, long)]
+    namespace: String,
+    #[arg(long)]
+    owner: Option<String>,
+    #[arg(long)]
+    revision: Option<u32>,
+    #[arg(long)]
+    context: Option<String>,
+    #[arg(long, default_value_t = 300)]
+    global_timeout: u64,
+    #[arg(long, default_value_t = true)]
+    verify_result: bool,
 }
 
 #[derive(Parser)]
@@ -83,118 +196,363 @@ struct RenderArgs {
     #[arg(long)]
     bindings: Vec<String>,
     #[arg(long)]
-    bindings_file: Option<String>,
+    bindings_file: Vec<PathBuf>,
+    #[arg(long)]
+    allow_env: bool,
+    #[arg(long)]
+    secrets: Option<PathBuf>,
+    #[arg(long)]
+    secrets_key: Option<PathBuf>,
+    #[arg(long)]
+    show_secrets: bool,
     #[arg(long)]
     current_sha: bool,
 }
 
+#[derive(Parser)]
+struct ValidateArgs {
+    #[command(flatten)]
+    template: TemplateArgs,
+    #[arg(long)]
+    context: Option<String>,
+    #[arg(long)]
+    crd_dir: Vec<PathBuf>,
+    #[arg(long)]
+    offline: bool,
+}
+
+#[derive(Parser)]
+struct LintArgs {
+    #[command(flatten)]
+    template: TemplateArgs,
+    #[arg(long)]
+    config: Option<PathBuf>,
+}
+
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
 
-    match cli.command {
+    let result = match cli.command {
         Commands::Deploy(args) => deploy(args).await,
+        Commands::Diff(args) => diff(args).await,
         Commands::GlobalDeploy(args) => global_deploy(args).await,
         Commands::Restart(args) => restart(args).await,
+        Commands::Rollback(args) => rollback(args).await,
         Commands::Render(args) => render(args).await,
+        Commands::Validate(args) => validate(args).await,
+        Commands::Lint(args) => lint(args).await,
+    };
+
+    if let Err(e) = result {
+        eprintln!("boom: {e}");
+        boom::output::event(
+            "error",
+            serde_json::json!({ "kind": e.kind(), "message": e.message(), "exit_code": e.exit_code() }),
+        );
+        process::exit(e.exit_code());
     }
 }
 
-async fn deploy(args: DeployArgs) {
-    let client = client::build_client(args.context.as_deref()).await;
+// Returns the merged bindings plus the decrypted secret strings for redaction.
+fn load_bindings(
+    files: &[PathBuf],
+    overrides: &[String],
+    secrets: Option<&Path>,
+    secrets_key: Option<&Path>,
+) -> Result<(serde_json::Value, Vec<String>), BoomError> {
+    let mut bindings = boom::render::load_bindings(files, overrides)?;
+    let Some(secrets) = secrets else {
+        return Ok((bindings, Vec::new()));
+    };
+    let decrypted =
+        boom::secrets::load_secrets_file(secrets, secrets_key).map_err(BoomError::Render)?;
+    let strings = boom::secrets::secret_strings(&decrypted);
+    bindings["secrets"] = decrypted;
+    Ok((bindings, strings))
+}
+
+fn load_resources(
+    args: &TemplateArgs,
+) -> Result<(Vec<boom::manifest::ResourceDescriptor>, Vec<String>), BoomError> {
+    load_cluster_resources(args, &serde_json::Map::new())
+}
 
-    let yaml = if let Some(ref values_path) = args.values {
-        let bindings = boom::render::load_bindings_file(values_path);
-        let templates = boom::render::load_templates(&args.dir);
-        match boom::render::render_templates(&templates, &bindings) {
-            Ok(output) => output,
-            Err(e) => {
-                eprintln!("boom: {e}");
-                process::exit(1);
-            }
+// Renders with one cluster's bindings merged over the shared ones.
+fn load_cluster_resources(
+    args: &TemplateArgs,
+    overrides: &serde_json::Map<String, serde_json::Value>,
+) -> Result<(Vec<boom::manifest::ResourceDescriptor>, Vec<String>), BoomError> {
+    let (mut bindings, secrets) = load_bindings(
+        &args.values,
+        &args.bindings,
+        args.secrets.as_deref(),
+        args.secrets_key.as_deref(),
+    )?;
+    boom::render::merge(&mut bindings, serde_json::Value::Object(overrides.clone()));
+    let options = boom::render::RenderOptions {
+        allow_env: args.allow_env,
+    };
+    let templates = boom::render::load_templates(&args.dir)?;
+    let files =
+        boom::render::render_files(&templates, &bindings, &options).map_err(BoomError::Render)?;
+    let mut resources = boom::manifest::parse_files(&files).map_err(BoomError::Render)?;
+    boom::manifest::set_owner_label(&mut resources, &args.owner()?);
+    Ok((resources, secrets))
+}
+
+async fn discover(
+    client: &kube::Client,
+    resources: &[boom::manifest::ResourceDescriptor],
+) -> Result<boom::discovery::ApiCatalog, BoomError> {
+    let mut catalog = boom::discovery::ApiCatalog::discover(client)
+        .await
+        .map_err(BoomError::Auth)?;
+    catalog.register_crds(resources);
+    Ok(catalog)
+}
+
+async fn deploy(args: DeployArgs) -> Result<(), BoomError> {
+    boom::output::set_format(args.output);
+    let targets = args.clusters.targets(&args.context)?;
+    match targets.as_slice() {
+        [] => Box::pin(deploy_cluster(&args, None, &serde_json::Map::new())).await,
+        [target] => {
+            Box::pin(deploy_cluster(
+                &args,
+                Some(&target.context),
+                &target.bindings,
+            ))
+            .await
         }
-    } else {
-        let templates = boom::render::load_templates(&args.dir);
-        let bindings = std::collections::HashMap::new();
-        match boom::render::render_templates(&templates, &bindings) {
-            Ok(output) => output,
-            Err(e) => {
-                eprintln!("boom: {e}");
-                process::exit(1);
-            }
+        _ => {
+            boom::clusters::run(&targets, args.clusters.mode(), |target| {
+                Box::pin(deploy_cluster(
+                    &args,
+                    Some(&target.context),
+                    &target.bindings,
+                ))
+            })
+            .await
         }
-    };
+    }
+}
+
+async fn deploy_cluster(
+    args: &DeployArgs,
+    context: Option<&str>,
+    overrides: &serde_json::Map<String, serde_json::Value>,
+) -> Result<(), BoomError> {
+    let client = boom::client::build_client(context).await?;
 
-    let resources = boom::manifest::parse_manifests(&yaml);
-    let ok = boom::deploy::run(
+    let owner = args.template.owner()?;
+    let (resources, _) = load_cluster_resources(&args.template, overrides)?;
+    let catalog = discover(&client, &resources).await?;
+    // Validated here rather than in deploy::run so an invalid multi-namespace
+    // deploy fails before any namespace is created.
+    if !args.skip_validation {
+        boom::validate::check(&client, &resources).await?;
+    }
+    let namespaces = if args.multi_namespace {
+        prepare_namespaces(&client, &catalog, &resources, args).await?
+    } else {
+        args.namespace.iter().cloned().collect()
+    };
+    let result = boom::deploy::run(
         client.clone(),
-        &args.namespace,
+        &catalog,
+        args.namespace.as_deref().unwrap_or_default(),
         resources.clone(),
-        args.verify_result,
-        args.global_timeout,
+        &boom::deploy::DeployOptions {
+            verify_result: args.verify_result,
+            global_timeout: args.global_timeout,
+            rollback_on_failure: args.rollback_on_failure,
+            validate: false,
+            canary: args.canary,
+        },
     )
     .await;
-    if ok {
-        if args.prune {
-            boom::output::info("[boom] pruning stale resources");
-            let deployed: Vec<boom::prune::ResourceDescriptor> = resources
-                .iter()
-                .map(|r| boom::prune::ResourceDescriptor {
-                    name: r.name.clone(),
-                    kind: r.kind.clone(),
-                    namespace: r.namespace.clone().unwrap_or(args.namespace.clone()),
-                })
-                .collect();
-            let stale = boom::prune::identify_stale(&deployed, &deployed);
-            if stale.is_empty() {
-                boom::output::success("[boom] no stale resources to prune");
-            } else {
-                boom::output::info(&format!("[boom] pruning {} stale resources", stale.len()));
-                if let Err(e) = boom::prune::execute(&client, &stale).await {
-                    boom::output::error(&format!("[boom] prune failed: {e}"));
-                    process::exit(1);
-                }
-            }
+
+    // History lives in one namespace; a multi-namespace deploy without a home
+    // namespace has nowhere to put it.
+    if let Some(history_namespace) = &args.namespace {
+        record_history(&client, history_namespace, &owner, &resources, &result).await;
+    } else {
+        boom::output::warn("[boom] deploy history not recorded (pass --namespace to choose where)");
+    }
+    result?;
+    prune(args, &client, &catalog, &owner, &resources, &namespaces).await
+}
+
+// Deploys that got as far as applying are recorded even when they fail, so a
+// bare `rollback` knows the newest revision isn't a good one.
+async fn record_history(
+    client: &kube::Client,
+    namespace: &str,
+    owner: &str,
+    resources: &[boom::manifest::ResourceDescriptor],
+    result: &Result<(), BoomError>,
+) {
+    let succeeded = match result {
+        Ok(()) => true,
+        Err(BoomError::Apply(_) | BoomError::Failed(_) | BoomError::Timeout(_)) => false,
+        Err(_) => return,
+    };
+    match boom::history::record(client, namespace, owner, resources, succeeded).await {
+        Ok(revision) if succeeded => {
+            boom::output::info(&format!("[boom] recorded revision {revision}"));
         }
-        process::exit(0);
+        Ok(revision) => boom::output::info(&format!("[boom] recorded failed revision {revision}")),
+        Err(e) => boom::output::warn(&format!("[boom] failed to record deploy history: {e}")),
+    }
+}
+
+async fn prune(
+    args: &DeployArgs,
+    client: &kube::Client,
+    catalog: &boom::discovery::ApiCatalog,
+    owner: &str,
+    resources: &[boom::manifest::ResourceDescriptor],
+    namespaces: &[String],
+) -> Result<(), BoomError> {
+    if !args.prune {
+        return Ok(());
+    }
+
+    boom::output::info("[boom] pruning stale resources");
+    let deployed: Vec<boom::prune::ResourceDescriptor> = resources
+        .iter()
+        .map(|r| boom::prune::ResourceDescriptor {
+            api_version: r.api_version.clone(),
+            name: r.name.clone(),
+            kind: r.kind.clone(),
+            namespace: r
+                .namespace
+                .clone()
+                .or_else(|| args.namespace.clone())
+                .unwrap_or_default(),
+        })
+        .collect();
+    let mut existing = Vec::new();
+    for namespace in namespaces {
+        existing.extend(
+            boom::prune::list_owned(client, catalog, namespace, owner, &deployed)
+                .await
+                .map_err(|e| BoomError::Apply(format!("prune failed: {e}")))?,
+        );
+    }
+    let stale = boom::prune::identify_stale(&deployed, &existing);
+    if stale.is_empty() {
+        boom::output::success("[boom] no stale resources to prune");
     } else {
-        process::exit(1);
+        boom::output::info(&format!("[boom] pruning {} stale resources", stale.len()));
+        boom::prune::execute(client, catalog, &stale)
+            .await
+            .map_err(|e| BoomError::Apply(format!("prune failed: {e}")))?;
     }
+    Ok(())
 }
 
-async fn global_deploy(args: GlobalDeployArgs) {
-    let client = client::build_client(args.context.as_deref()).await;
+// Lists the namespaces a --multi-namespace deploy will touch, checks them against
+// --allow-namespaces and creates any that don't exist yet.
+async fn prepare_namespaces(
+    client: &kube::Client,
+    catalog: &boom::discovery::ApiCatalog,
+    resources: &[boom::manifest::ResourceDescriptor],
+    args: &DeployArgs,
+) -> Result<Vec<String>, BoomError> {
+    let touched = boom::namespaces::touched(catalog, resources, args.namespace.as_deref())
+        .map_err(BoomError::Render)?;
+    boom::namespaces::check_allowed(&touched, &args.allow_namespaces).map_err(BoomError::Usage)?;
+    boom::output::info(&format!(
+        "[boom] namespaces: {}",
+        touched.iter().cloned().collect::<Vec<_>>().join(", ")
+    ));
 
-    let yaml = if let Some(ref values_path) = args.values {
-        let bindings = boom::render::load_bindings_file(values_path);
-        let templates = boom::render::load_templates(&args.dir);
-        match boom::render::render_templates(&templates, &bindings) {
-            Ok(output) => output,
-            Err(e) => {
-                eprintln!("boom: {e}");
-                process::exit(1);
-            }
-        }
+    let undeclared = boom::namespaces::undeclared(&touched, resources);
+    let created = boom::namespaces::ensure(client, &undeclared)
+        .await
+        .map_err(BoomError::Apply)?;
+    for namespace in created {
+        boom::output::success(&format!("[boom] created namespace {namespace}"));
+    }
+    Ok(touched.into_iter().collect())
+}
+
+async fn diff(args: DiffArgs) -> Result<(), BoomError> {
+    let client = boom::client::build_client(args.context.as_deref()).await?;
+
+    let (resources, secrets) = load_resources(&args.template)?;
+
+    let catalog = discover(&client, &resources).await?;
+    let changed = boom::diff::run(client, &catalog, &args.namespace, resources, &secrets)
+        .await
+        .map_err(|e| BoomError::Apply(format!("diff failed: {e}")))?;
+    if changed == 0 {
+        boom::output::success("[boom] no changes");
     } else {
-        let templates = boom::render::load_templates(&args.dir);
-        let bindings = std::collections::HashMap::new();
-        match boom::render::render_templates(&templates, &bindings) {
-            Ok(output) => output,
-            Err(e) => {
-                eprintln!("boom: {e}");
-                process::exit(1);
-            }
+        boom::output::info(&format!("[boom] {changed} resources would change"));
+    }
+    Ok(())
+}
+
+async fn global_deploy(args: GlobalDeployArgs) -> Result<(), BoomError> {
+    boom::output::set_format(args.output);
+    let targets = args.clusters.targets(&args.context)?;
+    match targets.as_slice() {
+        [] => Box::pin(global_deploy_cluster(&args, None, &serde_json::Map::new())).await,
+        [target] => {
+            Box::pin(global_deploy_cluster(
+                &args,
+                Some(&target.context),
+                &target.bindings,
+            ))
+            .await
         }
-    };
+        _ => {
+            boom::clusters::run(&targets, args.clusters.mode(), |target| {
+                Box::pin(global_deploy_cluster(
+                    &args,
+                    Some(&target.context),
+                    &target.bindings,
+                ))
+            })
+            .await
+        }
+    }
+}
 
-    let resources = boom::manifest::parse_manifests(&yaml);
-    boom::global_deploy::run(client, resources, args.verify_result, args.global_timeout).await;
+async fn global_deploy_cluster(
+    args: &GlobalDeployArgs,
+    context: Option<&str>,
+    overrides: &serde_json::Map<String, serde_json::Value>,
+) -> Result<(), BoomError> {
+    let client = boom::client::build_client(context).await?;
+
+    let (resources, _) = load_cluster_resources(&args.template, overrides)?;
+    let catalog = discover(&client, &resources).await?;
+    boom::global_deploy::run(
+        client,
+        &catalog,
+        resources,
+        &boom::deploy::DeployOptions {
+            verify_result: args.verify_result,
+            global_timeout: args.global_timeout,
+            rollback_on_failure: false,
+            validate: !args.skip_validation,
+            canary: false,
+        },
+    )
+    .await
 }
 
-async fn restart(args: RestartArgs) {
-    let client = client::build_client(args.context.as_deref()).await;
+async fn restart(args: RestartArgs) -> Result<(), BoomError> {
+    boom::output::set_format(args.output);
+    let client = boom::client::build_client(args.context.as_deref()).await?;
+    let catalog = discover(&client, &[]).await?;
     boom::restart::run(
         client,
+        &catalog,
         &args.namespace,
         &args.deployments,
         &args.statefulsets,
@@ -202,45 +560,110 @@ async fn restart(args: RestartArgs) {
         args.verify_result,
         args.global_timeout,
     )
-    .await;
+    .await
 }
 
-#[allow(clippy::unused_async)]
-async fn render(args: RenderArgs) {
-    let dir = args.template_dir.as_deref().unwrap_or(".");
-
-    let mut bindings = if let Some(ref file) = args.bindings_file {
-        boom::render::load_bindings_file(Path::new(file))
+async fn rollback(args: RollbackArgs) -> Result<(), BoomError> {
+    let client = boom::client::build_client(args.context.as_deref()).await?;
+    let owner = if let Some(owner) = args.owner {
+        owner
     } else {
-        std::collections::HashMap::new()
+        let owners = boom::history::list_owners(&client, &args.namespace)
+            .await
+            .map_err(BoomError::Apply)?;
+        boom::history::only_owner(&owners).map_err(BoomError::Usage)?
     };
+    boom::rollback::run(
+        client,
+        &args.namespace,
+        &owner,
+        args.revision,
+        &boom::deploy::DeployOptions {
+            verify_result: args.verify_result,
+            global_timeout: args.global_timeout,
+            rollback_on_failure: false,
+            // Revisions were accepted by the cluster when they were recorded.
+            validate: false,
+            canary: false,
+        },
+    )
+    .await
+}
+
+async fn validate(args: ValidateArgs) -> Result<(), BoomError> {
+    let (resources, _) = load_resources(&args.template)?;
+
+    let mut schemas = boom::validate::SchemaStore::default();
+    for dir in &args.crd_dir {
+        schemas.load_dir(dir).map_err(BoomError::Usage)?;
+    }
+    if !args.offline {
+        let client = boom::client::build_client(args.context.as_deref()).await?;
+        schemas.fetch_crds(&client).await.map_err(BoomError::Auth)?;
+    }
+    schemas.register_crds(&resources);
 
-    for (k, v) in boom::render::parse_bindings(&args.bindings) {
-        bindings.insert(k, v);
+    let report = boom::validate::validate_all(&resources, &schemas);
+    for resource in &report.unchecked {
+        boom::output::warn(&format!("[boom] no schema for {resource}; skipped"));
     }
+    boom::validate::report_errors(&report)?;
+    boom::output::success(&format!(
+        "[boom] {} resources valid",
+        resources.len() - report.unchecked.len()
+    ));
+    Ok(())
+}
+
+#[allow(clippy::unused_async)]
+async fn lint(args: LintArgs) -> Result<(), BoomError> {
+    let (resources, _) = load_resources(&args.template)?;
+
+    // `.boom-lint.yml` in the template directory applies unless --config is given.
+    let default_config = args.template.dir.join(".boom-lint.yml");
+    let config = match &args.config {
+        Some(path) => boom::lint::LintConfig::load(path).map_err(BoomError::Usage)?,
+        None if default_config.is_file() => {
+            boom::lint::LintConfig::load(&default_config).map_err(BoomError::Usage)?
+        }
+        None => boom::lint::LintConfig::default(),
+    };
+    boom::lint::report(&boom::lint::lint(&resources, &config))
+}
+
+#[allow(clippy::unused_async)]
+async fn render(args: RenderArgs) -> Result<(), BoomError> {
+    let dir = args.template_dir.as_deref().unwrap_or(".");
+
+    let (mut bindings, secrets) = load_bindings(
+        &args.bindings_file,
+        &args.bindings,
+        args.secrets.as_deref(),
+        args.secrets_key.as_deref(),
+    )?;
 
     if args.current_sha {
         let output = std::process::Command::new("git")
             .args(["rev-parse", "HEAD"])
             .output()
-            .unwrap_or_else(|e| {
-                eprintln!("boom: failed to run git rev-parse HEAD: {e}");
-                process::exit(1);
-            });
+            .map_err(|e| BoomError::Render(format!("failed to run git rev-parse HEAD: {e}")))?;
         if !output.status.success() {
-            eprintln!("boom: git rev-parse HEAD failed");
-            process::exit(1);
+            return Err(BoomError::Render("git rev-parse HEAD failed".to_string()));
         }
         let sha = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        bindings.insert("current_sha".to_string(), sha);
+        bindings["current_sha"] = serde_json::Value::String(sha);
     }
 
-    let templates = boom::render::load_templates(Path::new(dir));
-    match boom::render::render_templates(&templates, &bindings) {
-        Ok(output) => println!("{output}"),
-        Err(e) => {
-            eprintln!("{e}");
-            process::exit(1);
-        }
+    let templates = boom::render::load_templates(Path::new(dir))?;
+    let options = boom::render::RenderOptions {
+        allow_env: args.allow_env,
+    };
+    let output = boom::render::render_templates(&templates, &bindings, &options)
+        .map_err(BoomError::Render)?;
+    if args.show_secrets {
+        println!("{output}");
+    } else {
+        println!("{}", boom::secrets::redact(&output, &secrets));
     }
+    Ok(())
 }
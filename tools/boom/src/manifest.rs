@@ -1,9 +1,19 @@
+use std::path::Path;
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::render::RenderedFile;
+
+pub const OWNER_LABEL: &str = "boom.io/owner";
+
 #[derive(Debug, Clone)]
 pub struct ResourceDescriptor {
     pub api_version: String,
     pub kind: String,
     pub name: String,
     pub namespace: Option<String>,
+    pub source: String,
     pub raw: serde_yaml::Value,
 }
 
@@ -14,39 +24,93 @@ fn extract_field(doc: &serde_yaml::Value, key: &str) -> String {
         .to_string()
 }
 
-pub fn parse_manifests(yaml: &str) -> Vec<ResourceDescriptor> {
-    yaml.split("\n---")
-        .filter(|section| !section.trim().is_empty())
-        .filter_map(|section| {
-            let doc: serde_yaml::Value = serde_yaml::from_str(section).ok()?;
-            let metadata = doc.get("metadata")?;
-            let namespace = metadata
-                .get("namespace")
-                .and_then(serde_yaml::Value::as_str)
-                .map(String::from);
-            Some(ResourceDescriptor {
-                api_version: extract_field(&doc, "apiVersion"),
-                kind: extract_field(&doc, "kind"),
-                name: extract_field(metadata, "name"),
-                namespace,
-                raw: doc,
-            })
-        })
-        .collect()
+pub fn parse_manifests(yaml: &str) -> Result<Vec<ResourceDescriptor>, String> {
+    parse_source("<input>", yaml)
+}
+
+pub fn parse_files(files: &[RenderedFile]) -> Result<Vec<ResourceDescriptor>, String> {
+    let mut resources = Vec::new();
+    for file in files {
+        resources.extend(parse_source(&file.name, &file.content)?);
+    }
+    Ok(resources)
+}
+
+pub fn parse_source(source: &str, yaml: &str) -> Result<Vec<ResourceDescriptor>, String> {
+    let mut resources = Vec::new();
+
+    for (index, document) in serde_yaml::Deserializer::from_str(yaml).enumerate() {
+        let location = format!("{source}: document {}", index + 1);
+        let doc = serde_yaml::Value::deserialize(document)
+            .map_err(|e| format!("{location}: invalid YAML: {e}"))?;
+
+        // Empty and comment-only documents
+        if doc.is_null() {
+            continue;
+        }
+
+        if is_list(&doc) {
+            let items = doc["items"].as_sequence().cloned().unwrap_or_default();
+            for (item_index, item) in items.into_iter().enumerate() {
+                let item_location = format!("{location}: item {}", item_index + 1);
+                resources.push(describe(&item_location, source, item)?);
+            }
+        } else {
+            resources.push(describe(&location, source, doc)?);
+        }
+    }
+
+    Ok(resources)
+}
+
+// `kind: List` (what `kubectl get -o yaml` prints) or a core typed list such as
+// `ConfigMapList`; custom kinds that merely end in "List" are resources.
+fn is_list(doc: &serde_yaml::Value) -> bool {
+    let kind = extract_field(doc, "kind");
+    let list =
+        kind == "List" || (extract_field(doc, "apiVersion") == "v1" && kind.ends_with("List"));
+    list && doc["items"].is_sequence()
 }
 
-pub fn is_cluster_scoped(kind: &str) -> bool {
-    matches!(
+fn describe(
+    location: &str,
+    source: &str,
+    doc: serde_yaml::Value,
+) -> Result<ResourceDescriptor, String> {
+    if !doc.is_mapping() {
+        return Err(format!("{location}: expected a mapping"));
+    }
+
+    let api_version = extract_field(&doc, "apiVersion");
+    let kind = extract_field(&doc, "kind");
+    let name = doc
+        .get("metadata")
+        .map(|m| extract_field(m, "name"))
+        .unwrap_or_default();
+
+    for (field, value) in [
+        ("apiVersion", &api_version),
+        ("kind", &kind),
+        ("metadata.name", &name),
+    ] {
+        if value.is_empty() {
+            return Err(format!("{location}: missing {field}"));
+        }
+    }
+
+    let namespace = doc["metadata"]
+        .get("namespace")
+        .and_then(serde_yaml::Value::as_str)
+        .map(String::from);
+
+    Ok(ResourceDescriptor {
+        api_version,
         kind,
-        "Namespace"
-            | "ClusterRole"
-            | "ClusterRoleBinding"
-            | "CustomResourceDefinition"
-            | "PersistentVolume"
-            | "StorageClass"
-            | "IngressClass"
-            | "PriorityClass"
-    )
+        name,
+        namespace,
+        source: source.to_string(),
+        raw: doc,
+    })
 }
 
 pub fn priority_tier(kind: &str) -> u8 {
@@ -60,3 +124,64 @@ pub fn priority_tier(kind: &str) -> u8 {
 pub fn sort_by_priority(resources: &mut [ResourceDescriptor]) {
     resources.sort_by_key(|r| priority_tier(&r.kind));
 }
+
+// `<basename>-<hash>`: the hash covers the directory's path relative to its git
+// repository root (its absolute path outside a repository), so same-named
+// directories such as `foo/app` and `bar/app` never share an owner.
+pub fn owner_from_dir(dir: &Path) -> Result<String, String> {
+    let dir = dir
+        .canonicalize()
+        .map_err(|e| format!("cannot resolve {}: {e}", dir.display()))?;
+    let root = dir.ancestors().find(|a| a.join(".git").exists());
+    let key = root.and_then(|r| dir.strip_prefix(r).ok()).unwrap_or(&dir);
+    let hash = hex::encode(&Sha256::digest(key.to_string_lossy().as_bytes())[..4]);
+
+    let name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // Leaves room for the hash within the 63-character label value limit.
+    let name: String = name.chars().take(54).collect();
+    Ok(match sanitize_label_value(&name).as_str() {
+        "" => hash,
+        name => format!("{name}-{hash}"),
+    })
+}
+
+pub fn sanitize_label_value(value: &str) -> String {
+    let replaced: String = value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .take(63)
+        .collect();
+    replaced
+        .trim_matches(|c: char| !c.is_ascii_alphanumeric())
+        .to_string()
+}
+
+pub fn set_owner_label(resources: &mut [ResourceDescriptor], owner: &str) {
+    for resource in resources {
+        let Some(metadata) = resource
+            .raw
+            .get_mut("metadata")
+            .and_then(serde_yaml::Value::as_mapping_mut)
+        else {
+            continue;
+        };
+        let labels = metadata
+            .entry("labels".into())
+            .or_insert_with(|| serde_yaml::Value::Mapping(serde_yaml::Mapping::new()));
+        if !labels.is_mapping() {
+            *labels = serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
+        }
+        if let Some(labels) = labels.as_mapping_mut() {
+            labels.insert(OWNER_LABEL.into(), owner.into());
+        }
+    }
+}
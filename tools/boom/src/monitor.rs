@@ -1,12 +1,23 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::{self, Write};
 use std::time::Duration;
 
-use k8s_openapi::api::core::v1::Pod;
+use futures::StreamExt;
+use k8s_openapi::api::apps::v1::{
+    ControllerRevision, DaemonSet, Deployment, ReplicaSet, StatefulSet,
+};
+use k8s_openapi::api::core::v1::{ContainerStatus, Event, Pod};
 use kube::Api;
-use kube::api::{ApiResource, DynamicObject, ListParams, LogParams};
+use kube::api::{DynamicObject, ListParams, LogParams};
+use kube::runtime::{WatchStreamExt, watcher};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
 
-use crate::manifest::ResourceDescriptor;
+use crate::discovery::ApiCatalog;
+use crate::hints::{self, REQUIRED_ROLLOUT_ANNOTATION, RequiredRollout};
+use crate::manifest::{OWNER_LABEL, ResourceDescriptor};
+use crate::output;
+use crate::revert;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ResourceState {
@@ -25,18 +36,151 @@ impl fmt::Display for ResourceState {
     }
 }
 
+pub const READY_CONDITION_ANNOTATION: &str = "boom.io/ready-condition";
+
+// Conditions consulted, in order, for kinds without a dedicated check.
+pub const DEFAULT_READY_CONDITIONS: &[&str] = &["Ready", "Available"];
+
+// A False condition whose reason contains one of these is treated as terminal
+// rather than still converging.
+const TERMINAL_REASON_MARKERS: &[&str] = &["Fail", "Error", "Invalid", "Terminal"];
+
 pub fn is_ready(kind: &str, resource: &serde_json::Value) -> ResourceState {
+    let override_condition = str_field(
+        resource,
+        &["metadata", "annotations", READY_CONDITION_ANNOTATION],
+    );
+    if !override_condition.is_empty() {
+        return check_conditions(resource, &[override_condition]);
+    }
+
     match kind {
         "Deployment" => check_deployment(resource),
         "StatefulSet" => check_statefulset(resource),
         "DaemonSet" => check_daemonset(resource),
         "Pod" => check_pod(resource),
         "Job" => check_job(resource),
-        "ConfigMap" | "Secret" | "PersistentVolumeClaim" | "Service" => ResourceState::Ready,
+        "Service" => check_service(resource),
+        "Ingress" => check_ingress(resource),
+        "HorizontalPodAutoscaler" => check_hpa(resource),
+        "PersistentVolumeClaim" => check_pvc(resource),
+        "CustomResourceDefinition" => check_crd(resource),
+        "Namespace" => check_namespace(resource),
+        kind if is_passive(kind) => ResourceState::Ready,
+        _ => check_conditions(resource, DEFAULT_READY_CONDITIONS),
+    }
+}
+
+// Kinds with no status to wait on; they're ready as soon as the apply succeeds.
+pub const PASSIVE_KINDS: &[&str] = &[
+    "ConfigMap",
+    "Secret",
+    "ServiceAccount",
+    "Role",
+    "RoleBinding",
+    "ClusterRole",
+    "ClusterRoleBinding",
+    "NetworkPolicy",
+    "PodDisruptionBudget",
+    "CronJob",
+    "LimitRange",
+    "ResourceQuota",
+    "PriorityClass",
+    "StorageClass",
+    "IngressClass",
+    "ValidatingWebhookConfiguration",
+    "MutatingWebhookConfiguration",
+];
+
+pub fn is_passive(kind: &str) -> bool {
+    PASSIVE_KINDS.contains(&kind)
+}
+
+fn str_field<'a>(val: &'a serde_json::Value, path: &[&str]) -> &'a str {
+    let mut v = val;
+    for key in path {
+        v = match v.get(key) {
+            Some(inner) => inner,
+            None => return "",
+        };
+    }
+    v.as_str().unwrap_or("")
+}
+
+fn find_condition<'a>(
+    resource: &'a serde_json::Value,
+    condition: &str,
+) -> Option<&'a serde_json::Value> {
+    resource
+        .get("status")?
+        .get("conditions")?
+        .as_array()?
+        .iter()
+        .find(|c| c.get("type").and_then(serde_json::Value::as_str) == Some(condition))
+}
+
+fn condition_status<'a>(resource: &'a serde_json::Value, condition: &str) -> Option<&'a str> {
+    find_condition(resource, condition)?.get("status")?.as_str()
+}
+
+// Status written for an older spec says nothing about the current one.
+fn status_is_current(resource: &serde_json::Value) -> bool {
+    let observed = resource
+        .get("status")
+        .and_then(|s| s.get("observedGeneration"))
+        .and_then(serde_json::Value::as_i64);
+    let generation = resource
+        .get("metadata")
+        .and_then(|m| m.get("generation"))
+        .and_then(serde_json::Value::as_i64);
+    match (observed, generation) {
+        (Some(observed), Some(generation)) => observed >= generation,
+        _ => true,
+    }
+}
+
+fn check_conditions(resource: &serde_json::Value, conditions: &[&str]) -> ResourceState {
+    if !status_is_current(resource) {
+        return ResourceState::NotReady;
+    }
+    let Some(condition) = conditions
+        .iter()
+        .find_map(|name| find_condition(resource, name))
+    else {
+        return ResourceState::NotReady;
+    };
+
+    match str_field(condition, &["status"]) {
+        "True" => ResourceState::Ready,
+        "False" => {
+            let reason = str_field(condition, &["reason"]);
+            if TERMINAL_REASON_MARKERS.iter().any(|m| reason.contains(m)) {
+                ResourceState::Failed
+            } else {
+                ResourceState::NotReady
+            }
+        }
         _ => ResourceState::NotReady,
     }
 }
 
+fn has_load_balancer(resource: &serde_json::Value) -> bool {
+    resource
+        .get("status")
+        .and_then(|s| s.get("loadBalancer"))
+        .and_then(|lb| lb.get("ingress"))
+        .and_then(serde_json::Value::as_array)
+        .is_some_and(|ingress| !ingress.is_empty())
+}
+
+fn ready_if(ready: bool) -> ResourceState {
+    if ready {
+        ResourceState::Ready
+    } else {
+        ResourceState::NotReady
+    }
+}
+
 fn int_field(val: &serde_json::Value, path: &[&str]) -> i64 {
     let mut v = val;
     for key in path {
@@ -48,18 +192,60 @@ fn int_field(val: &serde_json::Value, path: &[&str]) -> i64 {
     v.as_i64().unwrap_or(0)
 }
 
+fn rollout_stalled(resource: &serde_json::Value) -> bool {
+    find_condition(resource, "Progressing").is_some_and(|c| {
+        str_field(c, &["status"]) == "False"
+            && str_field(c, &["reason"]) == "ProgressDeadlineExceeded"
+    })
+}
+
+// Kubernetes rounds a percentage maxUnavailable down; the default is 25%.
+fn max_unavailable(resource: &serde_json::Value, desired: i64) -> i64 {
+    let value = resource
+        .get("spec")
+        .and_then(|s| s.get("strategy"))
+        .and_then(|s| s.get("rollingUpdate"))
+        .and_then(|r| r.get("maxUnavailable"));
+    match value {
+        Some(serde_json::Value::Number(n)) => n.as_i64().unwrap_or(0),
+        Some(serde_json::Value::String(s)) => s
+            .strip_suffix('%')
+            .and_then(|p| p.parse::<i64>().ok())
+            .map_or(0, |p| desired * p / 100),
+        _ => desired / 4,
+    }
+}
+
 fn check_deployment(resource: &serde_json::Value) -> ResourceState {
+    if rollout_stalled(resource) {
+        return ResourceState::Failed;
+    }
+    let required = RequiredRollout::parse(str_field(
+        resource,
+        &["metadata", "annotations", REQUIRED_ROLLOUT_ANNOTATION],
+    ))
+    .unwrap_or_default();
+
     let desired = int_field(resource, &["spec", "replicas"]);
     let available = int_field(resource, &["status", "availableReplicas"]);
     let updated = int_field(resource, &["status", "updatedReplicas"]);
-    if available >= desired && updated >= desired {
-        ResourceState::Ready
-    } else {
-        ResourceState::NotReady
+    match required {
+        RequiredRollout::None => ResourceState::Ready,
+        RequiredRollout::Full => ready_if(available >= desired && updated >= desired),
+        RequiredRollout::MaxUnavailable => ready_if(
+            updated >= desired && available >= desired - max_unavailable(resource, desired),
+        ),
+        RequiredRollout::Percent(percent) => {
+            let needed = (desired * i64::from(percent) + 99) / 100;
+            ready_if(available >= needed && updated >= needed)
+        }
     }
 }
 
 fn check_statefulset(resource: &serde_json::Value) -> ResourceState {
+    if rollout_stalled(resource) {
+        return ResourceState::Failed;
+    }
     let desired = int_field(resource, &["spec", "replicas"]);
     let ready = int_field(resource, &["status", "readyReplicas"]);
     if ready >= desired {
@@ -70,6 +256,9 @@ fn check_statefulset(resource: &serde_json::Value) -> ResourceState {
 }
 
 fn check_daemonset(resource: &serde_json::Value) -> ResourceState {
+    if rollout_stalled(resource) {
+        return ResourceState::Failed;
+    }
     let desired = int_field(resource, &["status", "desiredNumberScheduled"]);
     let ready = int_field(resource, &["status", "numberReady"]);
     if ready >= desired {
@@ -79,24 +268,69 @@ fn check_daemonset(resource: &serde_json::Value) -> ResourceState {
     }
 }
 
-fn check_pod(resource: &serde_json::Value) -> ResourceState {
-    // Check for terminal failure states first
-    if let Some(statuses) = resource
-        .get("status")
-        .and_then(|s| s.get("containerStatuses"))
-        .and_then(|c| c.as_array())
+// Container states that won't resolve without a change to the spec or cluster.
+pub const FAILED_CONTAINER_REASONS: &[&str] = &[
+    "CrashLoopBackOff",
+    "ImagePullBackOff",
+    "ErrImagePull",
+    "CreateContainerConfigError",
+];
+
+pub fn pod_failure(pod: &serde_json::Value) -> Option<String> {
+    let status = pod.get("status")?;
+    let containers = ["initContainerStatuses", "containerStatuses"]
+        .iter()
+        .filter_map(|field| status.get(field).and_then(serde_json::Value::as_array))
+        .flatten();
+    for cs in containers {
+        let container = str_field(cs, &["name"]);
+        let waiting = str_field(cs, &["state", "waiting", "reason"]);
+        // A past OOM kill only counts while the container is still down because
+        // of it; one that restarted and is running again is healthy.
+        let oom_killed = str_field(cs, &["state", "terminated", "reason"]) == "OOMKilled"
+            || (waiting == "CrashLoopBackOff"
+                && str_field(cs, &["lastState", "terminated", "reason"]) == "OOMKilled");
+        if oom_killed {
+            return Some(format!("container {container}: OOMKilled"));
+        }
+        if FAILED_CONTAINER_REASONS.contains(&waiting) {
+            return Some(format!("container {container}: {waiting}"));
+        }
+    }
+
+    let scheduled = find_condition(pod, "PodScheduled")?;
+    if str_field(scheduled, &["status"]) == "False"
+        && str_field(scheduled, &["reason"]) == "Unschedulable"
     {
-        for cs in statuses {
-            let reason = cs
-                .get("state")
-                .and_then(|s| s.get("waiting"))
-                .and_then(|w| w.get("reason"))
-                .and_then(|r| r.as_str())
-                .unwrap_or("");
-            if reason == "CrashLoopBackOff" || reason == "ImagePullBackOff" {
-                return ResourceState::Failed;
+        return Some(format!(
+            "Unschedulable: {}",
+            str_field(scheduled, &["message"])
+        ));
+    }
+    None
+}
+
+// Why a resource reported Failed, for the state transition log.
+pub fn failure_reason(kind: &str, resource: &serde_json::Value) -> Option<String> {
+    if kind == "Pod" {
+        return pod_failure(resource);
+    }
+    let conditions = resource.get("status")?.get("conditions")?.as_array()?;
+    conditions
+        .iter()
+        .find(|c| str_field(c, &["status"]) == "False" && !str_field(c, &["reason"]).is_empty())
+        .map(|c| {
+            let reason = str_field(c, &["reason"]);
+            match str_field(c, &["message"]) {
+                "" => reason.to_string(),
+                message => format!("{reason}: {message}"),
             }
-        }
+        })
+}
+
+fn check_pod(resource: &serde_json::Value) -> ResourceState {
+    if pod_failure(resource).is_some() {
+        return ResourceState::Failed;
     }
 
     let phase = resource
@@ -146,19 +380,51 @@ fn check_job(resource: &serde_json::Value) -> ResourceState {
     }
 }
 
-async fn check_resource(client: &kube::Client, resource: &ResourceDescriptor) -> ResourceState {
+fn check_service(resource: &serde_json::Value) -> ResourceState {
+    if str_field(resource, &["spec", "type"]) == "LoadBalancer" {
+        ready_if(has_load_balancer(resource))
+    } else {
+        ResourceState::Ready
+    }
+}
+
+fn check_ingress(resource: &serde_json::Value) -> ResourceState {
+    ready_if(has_load_balancer(resource))
+}
+
+fn check_hpa(resource: &serde_json::Value) -> ResourceState {
+    ready_if(condition_status(resource, "AbleToScale") == Some("True"))
+}
+
+fn check_pvc(resource: &serde_json::Value) -> ResourceState {
+    match str_field(resource, &["status", "phase"]) {
+        "Bound" => ResourceState::Ready,
+        "Lost" => ResourceState::Failed,
+        _ => ResourceState::NotReady,
+    }
+}
+
+fn check_crd(resource: &serde_json::Value) -> ResourceState {
+    if condition_status(resource, "NamesAccepted") == Some("False") {
+        return ResourceState::Failed;
+    }
+    ready_if(condition_status(resource, "Established") == Some("True"))
+}
+
+fn check_namespace(resource: &serde_json::Value) -> ResourceState {
+    ready_if(str_field(resource, &["status", "phase"]) == "Active")
+}
+
+pub async fn check_resource(
+    client: &kube::Client,
+    catalog: &ApiCatalog,
+    resource: &ResourceDescriptor,
+) -> ResourceState {
     let ns = resource.namespace.as_deref().unwrap_or("default");
-    let (group, version) = crate::deploy::parse_api_version(&resource.api_version);
-
-    let ar = ApiResource {
-        group: group.to_string(),
-        version: version.to_string(),
-        api_version: resource.api_version.clone(),
-        kind: resource.kind.clone(),
-        plural: crate::deploy::pluralize(&resource.kind),
+    let Ok(api) = catalog.api(client, &resource.api_version, &resource.kind, ns) else {
+        return ResourceState::NotReady;
     };
 
-    let api: Api<DynamicObject> = Api::namespaced_with(client.clone(), ns, &ar);
     match api.get(&resource.name).await {
         Ok(obj) => {
             let val = serde_json::to_value(&obj).unwrap_or_default();
@@ -168,79 +434,429 @@ async fn check_resource(client: &kube::Client, resource: &ResourceDescriptor) ->
     }
 }
 
+// (resource key, state, reason when failed)
+type StateUpdate = (String, ResourceState, Option<String>);
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WatchGroup {
+    pub api_version: String,
+    pub kind: String,
+    pub namespace: String,
+}
+
+// One watcher per resource type and namespace; each group lists the names it tracks.
+pub fn watch_groups(
+    resources: &[ResourceDescriptor],
+) -> BTreeMap<WatchGroup, Vec<&ResourceDescriptor>> {
+    let mut groups: BTreeMap<WatchGroup, Vec<&ResourceDescriptor>> = BTreeMap::new();
+    for resource in resources {
+        let group = WatchGroup {
+            api_version: resource.api_version.clone(),
+            kind: resource.kind.clone(),
+            namespace: resource
+                .namespace
+                .clone()
+                .unwrap_or_else(|| "default".to_string()),
+        };
+        groups.entry(group).or_default().push(resource);
+    }
+    groups
+}
+
+// A single object is narrowed by name on the server; larger groups fall back
+// to their shared owner label, and anything else is filtered client-side.
+pub fn watcher_config(members: &[&ResourceDescriptor]) -> watcher::Config {
+    let config = watcher::Config::default();
+    if let [only] = members {
+        return config.fields(&format!("metadata.name={}", only.name));
+    }
+
+    let owners: BTreeSet<Option<&str>> = members
+        .iter()
+        .map(|r| {
+            r.raw
+                .get("metadata")
+                .and_then(|m| m.get("labels"))
+                .and_then(|l| l.get(OWNER_LABEL))
+                .and_then(serde_yaml::Value::as_str)
+        })
+        .collect();
+    match owners.into_iter().collect::<Vec<_>>().as_slice() {
+        [Some(owner)] => config.labels(&format!("{OWNER_LABEL}={owner}")),
+        _ => config,
+    }
+}
+
+// `namespace/Kind/name`: resources of one kind and name may exist in several
+// namespaces of a multi-namespace deploy.
+fn watch_key(namespace: &str, kind: &str, name: &str) -> String {
+    format!("{namespace}/{kind}/{name}")
+}
+
+fn resource_key(resource: &ResourceDescriptor) -> String {
+    watch_key(
+        resource.namespace.as_deref().unwrap_or("default"),
+        &resource.kind,
+        &resource.name,
+    )
+}
+
+async fn watch_group(
+    api: Api<DynamicObject>,
+    namespace: String,
+    kind: String,
+    names: HashSet<String>,
+    config: watcher::Config,
+    tx: mpsc::UnboundedSender<StateUpdate>,
+) {
+    let mut stream = watcher(api, config)
+        .default_backoff()
+        .applied_objects()
+        .boxed();
+    while let Some(event) = stream.next().await {
+        let Ok(obj) = event else { continue };
+        let Some(name) = obj.metadata.name.as_deref() else {
+            continue;
+        };
+        if !names.contains(name) {
+            continue;
+        }
+        let val = serde_json::to_value(&obj).unwrap_or_default();
+        let state = is_ready(&kind, &val);
+        let reason = match state {
+            ResourceState::Failed => failure_reason(&kind, &val),
+            _ => None,
+        };
+        if tx
+            .send((watch_key(&namespace, &kind, name), state, reason))
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+// The label selector for a workload's pods, from its manifest.
+pub fn pod_selector(resource: &ResourceDescriptor) -> Option<String> {
+    if !matches!(
+        resource.kind.as_str(),
+        "Deployment" | "StatefulSet" | "DaemonSet"
+    ) {
+        return None;
+    }
+    let labels = resource
+        .raw
+        .get("spec")?
+        .get("selector")?
+        .get("matchLabels")?
+        .as_mapping()?;
+    let pairs: Vec<String> = labels
+        .iter()
+        .filter_map(|(k, v)| Some(format!("{}={}", k.as_str()?, v.as_str()?)))
+        .collect();
+    (!pairs.is_empty()).then(|| pairs.join(","))
+}
+
+// The pod label that ties a workload's pods to the revision that created them.
+pub fn pod_revision_label(kind: &str) -> &'static str {
+    if kind == "Deployment" {
+        "pod-template-hash"
+    } else {
+        "controller-revision-hash"
+    }
+}
+
+// The revision label value of the rollout in progress: the newest ReplicaSet's
+// pod-template-hash, the StatefulSet's updateRevision, or the newest
+// ControllerRevision's hash. `owned` holds the workload's ReplicaSets or
+// ControllerRevisions. None until the controller has observed the latest spec.
+pub fn current_pod_hash(
+    kind: &str,
+    workload: &serde_json::Value,
+    owned: &[serde_json::Value],
+) -> Option<String> {
+    if int_field(workload, &["status", "observedGeneration"])
+        < int_field(workload, &["metadata", "generation"])
+    {
+        return None;
+    }
+    let newest = |revision: fn(&serde_json::Value) -> i64| {
+        owned
+            .iter()
+            .max_by_key(|o| revision(o))
+            .map(|o| str_field(o, &["metadata", "labels", pod_revision_label(kind)]))
+            .filter(|hash| !hash.is_empty())
+            .map(str::to_string)
+    };
+    match kind {
+        "Deployment" => newest(|rs| {
+            str_field(
+                rs,
+                &["metadata", "annotations", revert::REVISION_ANNOTATION],
+            )
+            .parse()
+            .unwrap_or(0)
+        }),
+        "StatefulSet" => Some(str_field(workload, &["status", "updateRevision"]).to_string())
+            .filter(|hash| !hash.is_empty()),
+        _ => newest(|cr| int_field(cr, &["revision"])),
+    }
+}
+
+fn owned_json<K: serde::Serialize + kube::Resource>(
+    items: &[K],
+    uid: &str,
+) -> Vec<serde_json::Value> {
+    items
+        .iter()
+        .filter(|o| {
+            o.meta()
+                .owner_references
+                .as_ref()
+                .is_some_and(|refs| refs.iter().any(|r| r.uid == uid))
+        })
+        .filter_map(|o| serde_json::to_value(o).ok())
+        .collect()
+}
+
+async fn fetch_current_pod_hash(
+    client: &kube::Client,
+    ns: &str,
+    resource: &ResourceDescriptor,
+    selector: &str,
+) -> Option<String> {
+    let params = ListParams::default().labels(selector);
+    let (workload, owned) = match resource.kind.as_str() {
+        "Deployment" => {
+            let workload = Api::<Deployment>::namespaced(client.clone(), ns)
+                .get(&resource.name)
+                .await
+                .ok()?;
+            let uid = workload.metadata.uid.clone()?;
+            let replicasets = Api::<ReplicaSet>::namespaced(client.clone(), ns)
+                .list(&params)
+                .await
+                .ok()?;
+            (
+                serde_json::to_value(&workload).ok()?,
+                owned_json(&replicasets.items, &uid),
+            )
+        }
+        "StatefulSet" => {
+            let workload = Api::<StatefulSet>::namespaced(client.clone(), ns)
+                .get(&resource.name)
+                .await
+                .ok()?;
+            (serde_json::to_value(&workload).ok()?, Vec::new())
+        }
+        _ => {
+            let workload = Api::<DaemonSet>::namespaced(client.clone(), ns)
+                .get(&resource.name)
+                .await
+                .ok()?;
+            let uid = workload.metadata.uid.clone()?;
+            let revisions = Api::<ControllerRevision>::namespaced(client.clone(), ns)
+                .list(&params)
+                .await
+                .ok()?;
+            (
+                serde_json::to_value(&workload).ok()?,
+                owned_json(&revisions.items, &uid),
+            )
+        }
+    };
+    current_pod_hash(&resource.kind, &workload, &owned)
+}
+
+// Workload status doesn't change while its pods crash-loop, so pods are
+// watched directly and a failing pod from the rollout in progress fails the
+// workload. Pods left over from earlier revisions are ignored.
+async fn watch_pods(
+    client: kube::Client,
+    resource: ResourceDescriptor,
+    selector: String,
+    tx: mpsc::UnboundedSender<StateUpdate>,
+) {
+    let ns = resource.namespace.as_deref().unwrap_or("default");
+    let key = resource_key(&resource);
+    let label = pod_revision_label(&resource.kind);
+    let api: Api<Pod> = Api::namespaced(client.clone(), ns);
+    let config = watcher::Config::default().labels(&selector);
+    let mut stream = watcher(api, config)
+        .default_backoff()
+        .applied_objects()
+        .boxed();
+    while let Some(event) = stream.next().await {
+        let Ok(pod) = event else { continue };
+        let val = serde_json::to_value(&pod).unwrap_or_default();
+        let Some(reason) = pod_failure(&val) else {
+            continue;
+        };
+        // Resolved per failure since the current revision moves during the rollout.
+        let Some(hash) = fetch_current_pod_hash(&client, ns, &resource, &selector).await else {
+            continue;
+        };
+        if str_field(&val, &["metadata", "labels", label]) != hash {
+            continue;
+        }
+        let pod_name = pod.metadata.name.unwrap_or_default();
+        let update = (
+            key.clone(),
+            ResourceState::Failed,
+            Some(format!("pod {pod_name}: {reason}")),
+        );
+        if tx.send(update).is_err() {
+            return;
+        }
+    }
+}
+
 pub async fn watch_resources(
     client: &kube::Client,
+    catalog: &ApiCatalog,
     resources: &[ResourceDescriptor],
     timeout_secs: u64,
 ) -> ResourceState {
-    let timeout = Duration::from_secs(timeout_secs);
-    let poll_interval = Duration::from_millis(500);
+    // Resources that opt out of verification aren't watched at all; the rest
+    // each get their own deadline, defaulting to the global timeout.
+    let start = tokio::time::Instant::now();
+    let mut watched = Vec::new();
+    let mut deadlines: HashMap<String, tokio::time::Instant> = HashMap::new();
+    for resource in resources {
+        let hints = hints::resource_hints(resource).unwrap_or_default();
+        if hints.skip_verify {
+            continue;
+        }
+        let timeout = hints.timeout.unwrap_or(Duration::from_secs(timeout_secs));
+        // Absurdly long annotation timeouts would overflow the clock; treat
+        // them as waiting forever (30 years).
+        let deadline = start
+            .checked_add(timeout)
+            .unwrap_or_else(|| start + Duration::from_hours(24 * 365 * 30));
+        deadlines.insert(resource_key(resource), deadline);
+        watched.push(resource.clone());
+    }
+    let resources = watched.as_slice();
+    if resources.is_empty() {
+        return ResourceState::Ready;
+    }
 
-    let mut last_states: HashMap<String, ResourceState> = HashMap::new();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watchers = JoinSet::new();
+
+    for (group, members) in watch_groups(resources) {
+        let Ok(api) = catalog.api(client, &group.api_version, &group.kind, &group.namespace) else {
+            continue;
+        };
+        let names = members.iter().map(|r| r.name.clone()).collect();
+        watchers.spawn(watch_group(
+            api,
+            group.namespace,
+            group.kind,
+            names,
+            watcher_config(&members),
+            tx.clone(),
+        ));
+    }
+    for resource in resources {
+        let Some(selector) = pod_selector(resource) else {
+            continue;
+        };
+        watchers.spawn(watch_pods(
+            client.clone(),
+            resource.clone(),
+            selector,
+            tx.clone(),
+        ));
+    }
+    drop(tx);
 
-    let result = tokio::time::timeout(timeout, async {
-        loop {
-            let mut all_ready = true;
-            let mut any_failed = false;
+    let mut last_states: HashMap<String, ResourceState> = HashMap::new();
 
-            for resource in resources {
-                let key = format!("{}/{}", resource.kind, resource.name);
-                let state = check_resource(client, resource).await;
+    let state = loop {
+        let pending_deadline = deadlines
+            .iter()
+            .filter(|(key, _)| last_states.get(*key) != Some(&ResourceState::Ready))
+            .min_by_key(|(_, deadline)| **deadline);
+        let Some((pending, deadline)) = pending_deadline else {
+            break ResourceState::Ready;
+        };
 
-                let prev = last_states.get(&key);
-                if prev.is_none_or(|p| *p != state) {
-                    eprintln!("[boom] {key} -> {state}");
-                    last_states.insert(key.clone(), state.clone());
+        tokio::select! {
+            update = rx.recv() => {
+                // Ends early only if every watcher has exited, i.e. no type could be resolved.
+                let Some((key, state, reason)) = update else {
+                    break ResourceState::NotReady;
+                };
+                if last_states.get(&key) != Some(&state) {
+                    output::state_change(&key, &state.to_string(), reason.as_deref());
+                    last_states.insert(key, state);
                 }
-
-                match &last_states[&key] {
-                    ResourceState::Failed => any_failed = true,
-                    ResourceState::NotReady => all_ready = false,
-                    ResourceState::Ready => {}
+                if last_states.values().any(|s| *s == ResourceState::Failed) {
+                    break ResourceState::Failed;
                 }
             }
-
-            if any_failed {
-                return ResourceState::Failed;
-            }
-            if all_ready {
-                return ResourceState::Ready;
+            () = tokio::time::sleep_until(*deadline) => {
+                output::state_change(pending, "Timeout", None);
+                break ResourceState::NotReady;
             }
-
-            tokio::time::sleep(poll_interval).await;
         }
-    })
-    .await;
+    };
 
-    match result {
-        Ok(state) => state,
-        Err(_) => ResourceState::NotReady, // timeout
-    }
+    watchers.abort_all();
+    state
 }
 
-pub async fn collect_diagnostics(client: &kube::Client, resource: &ResourceDescriptor) -> String {
-    let ns = resource.namespace.as_deref().unwrap_or("default");
+pub const DIAGNOSTIC_LOG_LINES: i64 = 25;
 
-    if resource.kind == "Pod" {
-        let api: Api<Pod> = Api::namespaced(client.clone(), ns);
-        match api.logs(&resource.name, &LogParams::default()).await {
-            Ok(logs) => return format!("--- Logs for Pod/{} ---\n{logs}", resource.name),
-            Err(e) => {
-                return format!(
-                    "--- Failed to fetch logs for Pod/{}: {e} ---",
-                    resource.name
-                );
-            }
-        }
+pub fn describe_container(cs: &ContainerStatus) -> String {
+    let state = cs.state.as_ref();
+    let current = if let Some(waiting) = state.and_then(|s| s.waiting.as_ref()) {
+        format!(
+            "waiting ({})",
+            waiting.reason.as_deref().unwrap_or("Unknown")
+        )
+    } else if let Some(terminated) = state.and_then(|s| s.terminated.as_ref()) {
+        format!(
+            "terminated ({}, exit code {})",
+            terminated.reason.as_deref().unwrap_or("Unknown"),
+            terminated.exit_code
+        )
+    } else if state.and_then(|s| s.running.as_ref()).is_some() {
+        "running".to_string()
+    } else {
+        "unknown".to_string()
+    };
+
+    let mut line = format!(
+        "container {}: {current}, ready={}, restarts={}",
+        cs.name, cs.ready, cs.restart_count
+    );
+    if let Some(last) = cs.last_state.as_ref().and_then(|s| s.terminated.as_ref()) {
+        let _ = write!(
+            line,
+            ", last terminated: {} (exit code {})",
+            last.reason.as_deref().unwrap_or("Unknown"),
+            last.exit_code
+        );
     }
+    line
+}
 
-    // For other kinds, list events filtered by involved object name
-    let api: Api<k8s_openapi::api::core::v1::Event> = Api::namespaced(client.clone(), ns);
-    let field_selector = format!("involvedObject.name={}", resource.name);
+fn indent_lines(text: &str) -> String {
+    text.lines().fold(String::new(), |mut out, line| {
+        let _ = writeln!(out, "    {line}");
+        out
+    })
+}
+
+async fn events_for(client: &kube::Client, ns: &str, kind: &str, name: &str) -> String {
+    let api: Api<Event> = Api::namespaced(client.clone(), ns);
+    let field_selector = format!("involvedObject.kind={kind},involvedObject.name={name}");
     let lp = ListParams::default().fields(&field_selector);
     match api.list(&lp).await {
         Ok(event_list) => {
-            let mut out = format!("--- Events for {}/{} ---\n", resource.kind, resource.name);
+            let mut out = format!("--- Events for {kind}/{name} ---\n");
             for event in &event_list.items {
                 let reason = event.reason.as_deref().unwrap_or("Unknown");
                 let message = event.message.as_deref().unwrap_or("");
@@ -248,9 +864,170 @@ pub async fn collect_diagnostics(client: &kube::Client, resource: &ResourceDescr
             }
             out
         }
-        Err(e) => format!(
-            "--- Failed to fetch events for {}/{}: {e} ---",
-            resource.kind, resource.name
-        ),
+        Err(e) => format!("--- Failed to fetch events for {kind}/{name}: {e} ---\n"),
+    }
+}
+
+async fn container_logs(api: &Api<Pod>, pod: &str, container: &str, previous: bool) -> String {
+    let params = LogParams {
+        container: Some(container.to_string()),
+        tail_lines: Some(DIAGNOSTIC_LOG_LINES),
+        previous,
+        ..Default::default()
+    };
+    let label = if previous { "previous logs" } else { "logs" };
+    match api.logs(pod, &params).await {
+        Ok(logs) if logs.trim().is_empty() => String::new(),
+        Ok(logs) => format!("  {label} for {container}:\n{}", indent_lines(&logs)),
+        Err(e) => format!("  failed to fetch {label} for {container}: {e}\n"),
+    }
+}
+
+async fn pod_diagnostics(client: &kube::Client, ns: &str, pod: &Pod) -> String {
+    let name = pod.metadata.name.as_deref().unwrap_or_default();
+    let status = pod.status.as_ref();
+    let phase = status.and_then(|s| s.phase.as_deref()).unwrap_or("Unknown");
+    let mut out = format!("--- Pod/{name} ({phase}) ---\n");
+
+    let val = serde_json::to_value(pod).unwrap_or_default();
+    if let Some(reason) = pod_failure(&val) {
+        let _ = writeln!(out, "  {reason}");
+    }
+
+    let api: Api<Pod> = Api::namespaced(client.clone(), ns);
+    let statuses = status
+        .into_iter()
+        .flat_map(|s| {
+            s.init_container_statuses
+                .iter()
+                .chain(s.container_statuses.iter())
+        })
+        .flatten();
+    for cs in statuses {
+        let _ = writeln!(out, "  {}", describe_container(cs));
+        out.push_str(&container_logs(&api, name, &cs.name, false).await);
+        if cs.restart_count > 0 {
+            out.push_str(&container_logs(&api, name, &cs.name, true).await);
+        }
+    }
+
+    out.push_str(&events_for(client, ns, "Pod", name).await);
+    out
+}
+
+// The newest ReplicaSet is the one the failing rollout created.
+async fn current_replicaset(
+    client: &kube::Client,
+    ns: &str,
+    deployment: &str,
+) -> Result<Option<ReplicaSet>, String> {
+    let deployments: Api<Deployment> = Api::namespaced(client.clone(), ns);
+    let deployment = deployments
+        .get(deployment)
+        .await
+        .map_err(|e| e.to_string())?;
+    let uid = deployment.metadata.uid.unwrap_or_default();
+
+    let replicasets: Api<ReplicaSet> = Api::namespaced(client.clone(), ns);
+    let list = replicasets
+        .list(&ListParams::default())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(list
+        .items
+        .into_iter()
+        .filter(|rs| {
+            rs.metadata
+                .owner_references
+                .as_ref()
+                .is_some_and(|refs| refs.iter().any(|r| r.uid == uid))
+        })
+        .max_by_key(|rs| revert::replicaset_revision(&rs.metadata).unwrap_or(0)))
+}
+
+// Walks Deployment -> ReplicaSet -> Pods (or StatefulSet/DaemonSet -> Pods)
+// and describes every pod that isn't ready.
+async fn workload_diagnostics(
+    client: &kube::Client,
+    ns: &str,
+    resource: &ResourceDescriptor,
+) -> String {
+    let mut out = events_for(client, ns, &resource.kind, &resource.name).await;
+
+    let owner = if resource.kind == "Deployment" {
+        match current_replicaset(client, ns, &resource.name).await {
+            Ok(Some(rs)) => {
+                let rs_name = rs.metadata.name.clone().unwrap_or_default();
+                out.push_str(&events_for(client, ns, "ReplicaSet", &rs_name).await);
+                rs.metadata.uid
+            }
+            Ok(None) => None,
+            Err(e) => {
+                let _ = writeln!(
+                    out,
+                    "--- Failed to find ReplicaSet for {}: {e} ---",
+                    resource.name
+                );
+                None
+            }
+        }
+    } else if resource.kind == "StatefulSet" {
+        let api: Api<StatefulSet> = Api::namespaced(client.clone(), ns);
+        api.get(&resource.name)
+            .await
+            .ok()
+            .and_then(|o| o.metadata.uid)
+    } else {
+        let api: Api<DaemonSet> = Api::namespaced(client.clone(), ns);
+        api.get(&resource.name)
+            .await
+            .ok()
+            .and_then(|o| o.metadata.uid)
+    };
+
+    let (Some(owner), Some(selector)) = (owner, pod_selector(resource)) else {
+        return out;
+    };
+    let pods: Api<Pod> = Api::namespaced(client.clone(), ns);
+    let list = match pods.list(&ListParams::default().labels(&selector)).await {
+        Ok(list) => list,
+        Err(e) => {
+            let _ = writeln!(
+                out,
+                "--- Failed to list pods for {}: {e} ---",
+                resource.name
+            );
+            return out;
+        }
+    };
+    for pod in &list.items {
+        let owned = pod
+            .metadata
+            .owner_references
+            .as_ref()
+            .is_some_and(|refs| refs.iter().any(|r| r.uid == owner));
+        let val = serde_json::to_value(pod).unwrap_or_default();
+        if owned && is_ready("Pod", &val) != ResourceState::Ready {
+            out.push_str(&pod_diagnostics(client, ns, pod).await);
+        }
+    }
+    out
+}
+
+pub async fn collect_diagnostics(client: &kube::Client, resource: &ResourceDescriptor) -> String {
+    let ns = resource.namespace.as_deref().unwrap_or("default");
+
+    match resource.kind.as_str() {
+        "Pod" => {
+            let api: Api<Pod> = Api::namespaced(client.clone(), ns);
+            match api.get(&resource.name).await {
+                Ok(pod) => pod_diagnostics(client, ns, &pod).await,
+                Err(e) => format!("--- Failed to fetch Pod/{}: {e} ---", resource.name),
+            }
+        }
+        "Deployment" | "StatefulSet" | "DaemonSet" => {
+            workload_diagnostics(client, ns, resource).await
+        }
+        kind => events_for(client, ns, kind, &resource.name).await,
     }
 }
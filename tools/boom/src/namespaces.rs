@@ -0,0 +1,99 @@
+use std::collections::BTreeSet;
+
+use k8s_openapi::api::core::v1::Namespace;
+use kube::Api;
+use kube::api::{ObjectMeta, PostParams};
+
+use crate::discovery::ApiCatalog;
+use crate::manifest::ResourceDescriptor;
+
+// Every namespace a multi-namespace deploy writes to: each namespaced resource's
+// own `metadata.namespace`, falling back to `default` when one is given.
+pub fn touched(
+    catalog: &ApiCatalog,
+    resources: &[ResourceDescriptor],
+    default: Option<&str>,
+) -> Result<BTreeSet<String>, String> {
+    let mut namespaces = BTreeSet::new();
+    let mut missing = Vec::new();
+    for resource in resources {
+        if catalog.is_namespaced(&resource.api_version, &resource.kind) == Some(false) {
+            continue;
+        }
+        match resource.namespace.as_deref().or(default) {
+            Some(ns) => {
+                namespaces.insert(ns.to_string());
+            }
+            None => missing.push(format!("{}/{}", resource.kind, resource.name)),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(format!(
+            "namespaced resources without metadata.namespace (set one or pass --namespace): {}",
+            missing.join(", ")
+        ));
+    }
+    Ok(namespaces)
+}
+
+// Refuses to touch any namespace the caller didn't list up front.
+pub fn check_allowed(touched: &BTreeSet<String>, allowed: &[String]) -> Result<(), String> {
+    let unexpected: Vec<&str> = touched
+        .iter()
+        .filter(|ns| !allowed.contains(ns))
+        .map(String::as_str)
+        .collect();
+    if unexpected.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "manifests touch namespaces not listed in --allow-namespaces: {}",
+        unexpected.join(", ")
+    ))
+}
+
+// Namespaces the manifests declare themselves are applied in tier 0 as usual.
+pub fn undeclared<'a>(
+    namespaces: &'a BTreeSet<String>,
+    resources: &[ResourceDescriptor],
+) -> Vec<&'a str> {
+    namespaces
+        .iter()
+        .filter(|ns| {
+            !resources
+                .iter()
+                .any(|r| r.kind == "Namespace" && r.name == **ns)
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+// Creates the given namespaces if they don't exist yet; returns the ones created.
+pub async fn ensure(client: &kube::Client, namespaces: &[&str]) -> Result<Vec<String>, String> {
+    let api: Api<Namespace> = Api::all(client.clone());
+    let mut created = Vec::new();
+    for ns in namespaces {
+        let exists = api
+            .get_opt(ns)
+            .await
+            .map_err(|e| format!("failed to read namespace {ns}: {e}"))?
+            .is_some();
+        if exists {
+            continue;
+        }
+        let namespace = Namespace {
+            metadata: ObjectMeta {
+                name: Some((*ns).to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        match api.create(&PostParams::default(), &namespace).await {
+            Ok(_) => created.push((*ns).to_string()),
+            // Created concurrently by someone else.
+            Err(kube::Error::Api(e)) if e.code == 409 => {}
+            Err(e) => return Err(format!("failed to create namespace {ns}: {e}")),
+        }
+    }
+    Ok(created)
+}
@@ -1,22 +1,233 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+
 use colored::Colorize;
+use serde_json::json;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    // One JSON event per line on stdout; human-readable text moves to stderr.
+    Json,
+}
+
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+type SummaryRow = (String, String, String, String);
+
+// Set while deploying to one cluster of a multi-cluster run: messages are
+// prefixed with the context, events carry a `cluster` field, and summary rows
+// are collected for the combined table instead of being printed.
+#[derive(Clone)]
+struct ClusterScope {
+    context: String,
+    rows: Arc<Mutex<Vec<SummaryRow>>>,
+}
+
+tokio::task_local! {
+    static CLUSTER: ClusterScope;
+}
+
+pub async fn in_cluster<F: Future>(context: &str, future: F) -> (F::Output, Vec<SummaryRow>) {
+    let scope = ClusterScope {
+        context: context.to_string(),
+        rows: Arc::new(Mutex::new(Vec::new())),
+    };
+    let rows = Arc::clone(&scope.rows);
+    let output = CLUSTER.scope(scope, future).await;
+    let rows = std::mem::take(
+        &mut *rows
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner),
+    );
+    (output, rows)
+}
+
+// Task-locals don't follow `tokio::spawn`; wrap spawned futures in this so
+// their output stays tagged with (and collected for) the spawning cluster.
+pub fn in_current_cluster<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let scope = CLUSTER.try_with(Clone::clone).ok();
+    async move {
+        match scope {
+            Some(scope) => CLUSTER.scope(scope, future).await,
+            None => future.await,
+        }
+    }
+}
+
+fn current_cluster() -> Option<String> {
+    CLUSTER.try_with(|scope| scope.context.clone()).ok()
+}
+
+fn tag(msg: &str) -> String {
+    match current_cluster() {
+        Some(context) => format!("[{context}] {msg}"),
+        None => msg.to_string(),
+    }
+}
+
+pub fn set_format(format: OutputFormat) {
+    let _ = FORMAT.set(format);
+}
+
+pub fn is_json() -> bool {
+    FORMAT.get() == Some(&OutputFormat::Json)
+}
+
+pub fn event_json(event: &str, fields: serde_json::Value) -> serde_json::Value {
+    let mut out = json!({ "event": event });
+    if let (Some(out), Some(context)) = (out.as_object_mut(), current_cluster()) {
+        out.insert("cluster".to_string(), context.into());
+    }
+    if let (Some(out), serde_json::Value::Object(fields)) = (out.as_object_mut(), fields) {
+        out.extend(fields);
+    }
+    out
+}
+
+pub fn event(event: &str, fields: serde_json::Value) {
+    if is_json() {
+        println!("{}", event_json(event, fields));
+    }
+}
 
 pub fn info(msg: &str) {
-    println!("{}", msg.cyan());
+    let msg = tag(msg);
+    if is_json() {
+        eprintln!("{}", msg.cyan());
+    } else {
+        println!("{}", msg.cyan());
+    }
 }
 
 pub fn success(msg: &str) {
-    println!("{}", msg.green());
+    let msg = tag(msg);
+    if is_json() {
+        eprintln!("{}", msg.green());
+    } else {
+        println!("{}", msg.green());
+    }
 }
 
 pub fn warn(msg: &str) {
-    println!("{}", msg.yellow());
+    let msg = tag(msg);
+    if is_json() {
+        eprintln!("{}", msg.yellow());
+    } else {
+        println!("{}", msg.yellow());
+    }
 }
 
 pub fn error(msg: &str) {
-    eprintln!("{}", msg.red());
+    eprintln!("{}", tag(msg).red());
+}
+
+pub fn diff(text: &str) {
+    for line in text.lines() {
+        if line.starts_with("+++") || line.starts_with("---") {
+            println!("{}", line.bold());
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else if line.starts_with('-') {
+            println!("{}", line.red());
+        } else if line.starts_with("@@") {
+            println!("{}", line.cyan());
+        } else {
+            println!("{line}");
+        }
+    }
+}
+
+pub fn applied(kind: &str, name: &str, failure: Option<&str>) {
+    match failure {
+        None => success(&format!("[OK] {kind}/{name}")),
+        Some(err) => error(&format!("[FAIL] {kind}/{name}: {err}")),
+    }
+    event(
+        "applied",
+        json!({ "kind": kind, "name": name, "ok": failure.is_none(), "error": failure }),
+    );
+}
+
+pub fn state_change(resource: &str, state: &str, reason: Option<&str>) {
+    match reason {
+        Some(reason) => eprintln!("[boom] {resource} -> {state} ({reason})"),
+        None => eprintln!("[boom] {resource} -> {state}"),
+    }
+    event(
+        "state",
+        json!({ "resource": resource, "state": state, "reason": reason }),
+    );
+}
+
+pub fn diagnostics(resource: &str, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    if is_json() {
+        event("diagnostics", json!({ "resource": resource, "text": text }));
+    } else {
+        error(text);
+    }
+}
+
+pub fn invalid(source: &str, resource: &str, path: &str, message: &str) {
+    if is_json() {
+        event(
+            "invalid",
+            json!({ "source": source, "resource": resource, "path": path, "message": message }),
+        );
+    } else {
+        error(&format!(
+            "[INVALID] {source}: {resource}: {path}: {message}"
+        ));
+    }
+}
+
+// The outcome of the whole run: "succeeded", "failed" or "timeout".
+pub fn finished(result: &str) {
+    event("finished", json!({ "result": result }));
+}
+
+// One line of a hook pod's log, streamed as it arrives.
+pub fn hook_log(resource: &str, line: &str) {
+    if is_json() {
+        event("log", json!({ "resource": resource, "line": line }));
+    } else {
+        eprintln!("{} {line}", format!("[{resource}]").dimmed());
+    }
+}
+
+pub fn summary_json(rows: &[(String, String, String, String)]) -> serde_json::Value {
+    let resources: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|(name, kind, status, duration)| {
+            json!({ "name": name, "kind": kind, "status": status, "duration": duration })
+        })
+        .collect();
+    event_json("summary", json!({ "resources": resources }))
 }
 
 pub fn summary_table(rows: &[(String, String, String, String)]) {
+    let collected = CLUSTER.try_with(|scope| {
+        scope
+            .rows
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .extend_from_slice(rows);
+    });
+    if collected.is_ok() {
+        return;
+    }
+    if is_json() {
+        println!("{}", summary_json(rows));
+        return;
+    }
+    print_rows(rows);
+}
+
+fn print_rows(rows: &[SummaryRow]) {
     println!(
         "{:<40} {:<30} {:<12} Duration",
         "Resource", "Kind", "Status"
@@ -25,10 +236,41 @@ pub fn summary_table(rows: &[(String, String, String, String)]) {
     for (resource, kind, status, duration) in rows {
         let line = format!("{resource:<40} {kind:<30} {status:<12} {duration}");
         match status.as_str() {
-            "OK" | "Ready" => println!("{}", line.green()),
-            "Failed" | "Error" => println!("{}", line.red()),
-            "Timeout" => println!("{}", line.yellow()),
+            "OK" | "Ready" | "Promoted" => println!("{}", line.green()),
+            "Failed" | "Error" | "RollbackFailed" | "Aborted" => println!("{}", line.red()),
+            "Timeout" | "RolledBack" => println!("{}", line.yellow()),
             _ => println!("{line}"),
         }
     }
 }
+
+// (context, result, rows) per cluster, in the order the clusters were given.
+pub fn cluster_summary_table(clusters: &[(String, String, Vec<SummaryRow>)]) {
+    if is_json() {
+        let clusters: Vec<serde_json::Value> = clusters
+            .iter()
+            .map(|(context, result, rows)| {
+                let mut summary = summary_json(rows);
+                summary["cluster"] = context.clone().into();
+                summary["result"] = result.clone().into();
+                summary
+            })
+            .collect();
+        println!(
+            "{}",
+            event_json("cluster_summary", json!({ "clusters": clusters }))
+        );
+        return;
+    }
+    for (context, result, rows) in clusters {
+        let header = format!("== {context}: {result}");
+        match result.as_str() {
+            "succeeded" => println!("\n{}", header.green().bold()),
+            "skipped" => println!("\n{}", header.bold()),
+            _ => println!("\n{}", header.red().bold()),
+        }
+        if !rows.is_empty() {
+            print_rows(rows);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::hints::annotation;
+use crate::manifest::{self, ResourceDescriptor};
+
+pub const PHASE_ANNOTATION: &str = "boom.io/phase";
+pub const DEPENDS_ON_ANNOTATION: &str = "boom.io/depends-on";
+
+fn key(resource: &ResourceDescriptor) -> String {
+    format!("{}/{}", resource.kind, resource.name)
+}
+
+fn explicit_phase(resource: &ResourceDescriptor) -> Result<i64, String> {
+    annotation(resource, PHASE_ANNOTATION).map_or(Ok(0), |value| {
+        value.trim().parse().map_err(|_| {
+            format!(
+                "{}: invalid {PHASE_ANNOTATION} {value:?} (expected an integer)",
+                key(resource)
+            )
+        })
+    })
+}
+
+fn dependencies(resource: &ResourceDescriptor) -> Vec<&str> {
+    annotation(resource, DEPENDS_ON_ANNOTATION)
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// A resource runs in its `boom.io/phase` (default 0), pushed later if needed so
+// it lands strictly after every resource named in `boom.io/depends-on`.
+pub fn effective_phases(resources: &[ResourceDescriptor]) -> Result<Vec<i64>, String> {
+    // Keyed by namespace too: `Kind/name` refers to the dependent's own namespace,
+    // `namespace/Kind/name` to another one.
+    let index: HashMap<(Option<&str>, String), usize> = resources
+        .iter()
+        .enumerate()
+        .map(|(i, r)| ((r.namespace.as_deref(), key(r)), i))
+        .collect();
+
+    let mut deps = Vec::with_capacity(resources.len());
+    for resource in resources {
+        let mut resolved = Vec::new();
+        for dep in dependencies(resource) {
+            let lookup = match dep.split_once('/') {
+                Some((namespace, rest)) if rest.contains('/') => {
+                    (Some(namespace), rest.to_string())
+                }
+                _ => (resource.namespace.as_deref(), dep.to_string()),
+            };
+            let i = index.get(&lookup).ok_or_else(|| {
+                format!(
+                    "{} depends on {dep}, which is not in this deploy",
+                    key(resource)
+                )
+            })?;
+            resolved.push(*i);
+        }
+        deps.push(resolved);
+    }
+
+    let mut phases: Vec<Option<i64>> = vec![None; resources.len()];
+    let mut visiting = vec![false; resources.len()];
+    for i in 0..resources.len() {
+        resolve(i, resources, &deps, &mut phases, &mut visiting)?;
+    }
+    Ok(phases.into_iter().map(Option::unwrap_or_default).collect())
+}
+
+fn resolve(
+    i: usize,
+    resources: &[ResourceDescriptor],
+    deps: &[Vec<usize>],
+    phases: &mut [Option<i64>],
+    visiting: &mut [bool],
+) -> Result<i64, String> {
+    if let Some(phase) = phases[i] {
+        return Ok(phase);
+    }
+    if visiting[i] {
+        return Err(format!("dependency cycle involving {}", key(&resources[i])));
+    }
+    visiting[i] = true;
+
+    let mut phase = explicit_phase(&resources[i])?;
+    for &dep in &deps[i] {
+        phase = phase.max(resolve(dep, resources, deps, phases, visiting)? + 1);
+    }
+
+    visiting[i] = false;
+    phases[i] = Some(phase);
+    Ok(phase)
+}
+
+// Groups resources into phases in ascending order, keeping their relative order.
+pub fn plan(
+    resources: &[ResourceDescriptor],
+) -> Result<Vec<(i64, Vec<ResourceDescriptor>)>, String> {
+    let phases = effective_phases(resources)?;
+    let mut grouped: BTreeMap<i64, Vec<ResourceDescriptor>> = BTreeMap::new();
+    for (resource, phase) in resources.iter().zip(phases) {
+        grouped.entry(phase).or_default().push(resource.clone());
+    }
+    Ok(grouped.into_iter().collect())
+}
+
+// Pulls the tier 0/1 resources (Namespaces, ServiceAccounts, CRDs, ConfigMaps,
+// Secrets, ...) out of every phase so they can be applied ahead of pre-deploy
+// hooks and canaries, which would otherwise start against missing or stale
+// dependencies.
+pub fn split_dependencies(
+    phases: Vec<(i64, Vec<ResourceDescriptor>)>,
+) -> (Vec<ResourceDescriptor>, Vec<(i64, Vec<ResourceDescriptor>)>) {
+    let mut dependencies = Vec::new();
+    let mut remaining = Vec::new();
+    for (phase, members) in phases {
+        let (deps, rest): (Vec<_>, Vec<_>) = members
+            .into_iter()
+            .partition(|r| manifest::priority_tier(&r.kind) < 2);
+        dependencies.extend(deps);
+        if !rest.is_empty() {
+            remaining.push((phase, rest));
+        }
+    }
+    (dependencies, remaining)
+}
@@ -1,42 +1,124 @@
 use std::collections::HashSet;
 
+use kube::api::{DeleteParams, ListParams, PropagationPolicy};
+
+use crate::discovery::ApiCatalog;
+use crate::manifest::OWNER_LABEL;
+
 #[derive(Debug, Clone)]
 pub struct ResourceDescriptor {
+    pub api_version: String,
     pub name: String,
     pub kind: String,
     pub namespace: String,
 }
 
+// Namespaced kinds always checked for stale objects, mirroring kubectl's prune allowlist.
+const PRUNABLE_KINDS: &[(&str, &str)] = &[
+    ("v1", "ConfigMap"),
+    ("v1", "PersistentVolumeClaim"),
+    ("v1", "Pod"),
+    ("v1", "ReplicationController"),
+    ("v1", "Secret"),
+    ("v1", "Service"),
+    ("v1", "ServiceAccount"),
+    ("apps/v1", "DaemonSet"),
+    ("apps/v1", "Deployment"),
+    ("apps/v1", "ReplicaSet"),
+    ("apps/v1", "StatefulSet"),
+    ("batch/v1", "CronJob"),
+    ("batch/v1", "Job"),
+    ("networking.k8s.io/v1", "Ingress"),
+];
+
 pub fn identify_stale(
     deployed: &[ResourceDescriptor],
     existing: &[ResourceDescriptor],
 ) -> Vec<ResourceDescriptor> {
-    let deployed_set: HashSet<(&str, &str)> = deployed
-        .iter()
-        .map(|r| (r.name.as_str(), r.kind.as_str()))
-        .collect();
+    let key = |r: &ResourceDescriptor| (r.namespace.clone(), r.name.clone(), r.kind.clone());
+    let deployed_set: HashSet<(String, String, String)> = deployed.iter().map(key).collect();
 
     existing
         .iter()
-        .filter(|r| !deployed_set.contains(&(r.name.as_str(), r.kind.as_str())))
+        .filter(|r| !deployed_set.contains(&key(r)))
         .cloned()
         .collect()
 }
 
-pub async fn execute(client: &kube::Client, stale: &[ResourceDescriptor]) -> Result<(), String> {
-    use kube::api::{Api, ApiResource, DeleteParams, DynamicObject, PropagationPolicy};
+pub fn prunable_kinds(deployed: &[ResourceDescriptor]) -> Vec<(String, String)> {
+    let mut kinds: Vec<(String, String)> = PRUNABLE_KINDS
+        .iter()
+        .map(|(api_version, kind)| ((*api_version).to_string(), (*kind).to_string()))
+        .collect();
 
-    for resource in stale {
-        let ar = ApiResource {
-            group: String::new(),
-            version: "v1".to_string(),
-            api_version: "v1".to_string(),
-            kind: resource.kind.clone(),
-            plural: crate::deploy::pluralize(&resource.kind),
-        };
+    for resource in deployed {
+        if !kinds.iter().any(|(_, kind)| *kind == resource.kind) {
+            kinds.push((resource.api_version.clone(), resource.kind.clone()));
+        }
+    }
+
+    kinds
+}
+
+pub async fn list_owned(
+    client: &kube::Client,
+    catalog: &ApiCatalog,
+    namespace: &str,
+    owner: &str,
+    deployed: &[ResourceDescriptor],
+) -> Result<Vec<ResourceDescriptor>, String> {
+    let lp = ListParams::default().labels(&format!("{OWNER_LABEL}={owner}"));
+    let mut existing = Vec::new();
+
+    for (api_version, kind) in prunable_kinds(deployed) {
+        // Kinds the cluster doesn't serve can't have stale objects
+        if catalog.is_namespaced(&api_version, &kind) != Some(true) {
+            continue;
+        }
+        let api = catalog.api(client, &api_version, &kind, namespace)?;
+
+        let list = api
+            .list(&lp)
+            .await
+            .map_err(|e| format!("failed to list {kind}: {e}"))?;
 
-        let api: Api<DynamicObject> =
-            Api::namespaced_with(client.clone(), &resource.namespace, &ar);
+        for obj in list.items {
+            // Skip controller-managed children (e.g. ReplicaSets, Pods)
+            if obj
+                .metadata
+                .owner_references
+                .as_ref()
+                .is_some_and(|refs| !refs.is_empty())
+            {
+                continue;
+            }
+            let Some(name) = obj.metadata.name else {
+                continue;
+            };
+            existing.push(ResourceDescriptor {
+                api_version: api_version.clone(),
+                name,
+                kind: kind.clone(),
+                namespace: namespace.to_string(),
+            });
+        }
+    }
+
+    Ok(existing)
+}
+
+pub async fn execute(
+    client: &kube::Client,
+    catalog: &ApiCatalog,
+    stale: &[ResourceDescriptor],
+) -> Result<(), String> {
+    for resource in stale {
+        let api = catalog.api(
+            client,
+            &resource.api_version,
+            &resource.kind,
+            &resource.namespace,
+        )?;
 
         let dp = DeleteParams {
             propagation_policy: Some(PropagationPolicy::Background),
@@ -1,100 +1,222 @@
-use std::collections::HashMap;
+use std::fs;
 use std::path::{Path, PathBuf};
-use std::{fs, process};
 
 use minijinja::{Environment, UndefinedBehavior};
 
+use crate::error::BoomError;
+use crate::filters;
+
+pub const PARTIALS_DIR: &str = "_partials";
+
 #[derive(Debug)]
 pub struct TemplateFile {
     pub path: PathBuf,
+    pub name: String,
     pub content: String,
     pub is_template: bool,
+    pub is_partial: bool,
 }
 
-pub fn load_templates(dir: &Path) -> Vec<TemplateFile> {
-    let mut templates: Vec<TemplateFile> = fs::read_dir(dir)
-        .unwrap_or_else(|e| {
-            eprintln!("boom: cannot read directory {}: {e}", dir.display());
-            process::exit(1);
-        })
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            let path = entry.path();
-            if !path.is_file() {
-                return None;
-            }
-            let name = path.file_name()?.to_str()?;
-            let is_template = name.ends_with(".yml.j2") || name.ends_with(".yaml.j2");
-            let is_plain = name.ends_with(".yml") || name.ends_with(".yaml");
-            if !is_template && !is_plain {
-                return None;
-            }
-            let content = fs::read_to_string(&path).ok()?;
-            Some(TemplateFile {
-                path,
-                content,
-                is_template,
-            })
-        })
+pub fn load_templates(dir: &Path) -> Result<Vec<TemplateFile>, BoomError> {
+    let mut templates = Vec::new();
+    collect_templates(dir, dir, &mut templates)?;
+    templates.sort_by(|a, b| (a.is_partial, &a.path).cmp(&(b.is_partial, &b.path)));
+    Ok(templates)
+}
+
+fn collect_templates(
+    root: &Path,
+    dir: &Path,
+    templates: &mut Vec<TemplateFile>,
+) -> Result<(), BoomError> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| BoomError::Render(format!("cannot read directory {}: {e}", dir.display())))?;
+
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if file_name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_templates(root, &path, templates)?;
+            continue;
+        }
+        if !path.is_file() {
+            continue;
+        }
+
+        let partials_root = root.join(PARTIALS_DIR);
+        let is_partial = path.starts_with(&partials_root);
+        let is_template = file_name.ends_with(".yml.j2") || file_name.ends_with(".yaml.j2");
+        let is_plain = file_name.ends_with(".yml") || file_name.ends_with(".yaml");
+        if !is_partial && !is_template && !is_plain {
+            continue;
+        }
+
+        let base = if is_partial { &partials_root } else { root };
+        let Some(name) = relative_name(base, &path) else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        templates.push(TemplateFile {
+            path,
+            name,
+            content,
+            is_template: is_template || is_partial,
+            is_partial,
+        });
+    }
+    Ok(())
+}
+
+fn relative_name(base: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(base).ok()?;
+    let parts: Option<Vec<&str>> = relative
+        .components()
+        .map(|c| c.as_os_str().to_str())
         .collect();
+    Some(parts?.join("/"))
+}
 
-    templates.sort_by(|a, b| a.path.file_name().cmp(&b.path.file_name()));
-    templates
+#[derive(Debug)]
+pub struct RenderedFile {
+    pub name: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    pub allow_env: bool,
+}
+
+pub fn environment<'source>(options: &RenderOptions) -> Environment<'source> {
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    filters::register(&mut env, options.allow_env);
+    env
 }
 
 pub fn render_templates(
     templates: &[TemplateFile],
-    bindings: &HashMap<String, String>,
+    bindings: &serde_json::Value,
+    options: &RenderOptions,
 ) -> Result<String, String> {
-    let mut env = Environment::new();
-    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    let documents: Vec<String> = render_files(templates, bindings, options)?
+        .into_iter()
+        .map(|file| {
+            if file.content.ends_with('\n') {
+                file.content
+            } else {
+                format!("{}\n", file.content)
+            }
+        })
+        .collect();
+
+    Ok(documents.join("---\n"))
+}
+
+pub fn render_files(
+    templates: &[TemplateFile],
+    bindings: &serde_json::Value,
+    options: &RenderOptions,
+) -> Result<Vec<RenderedFile>, String> {
+    let mut env = environment(options);
+
+    // Partials are only reachable via `{% include %}`/`{% import %}`, named
+    // relative to the partials directory.
+    for partial in templates.iter().filter(|t| t.is_partial) {
+        env.add_template(&partial.name, &partial.content)
+            .map_err(|e| format!("boom: render error in {PARTIALS_DIR}/{}: {e}", partial.name))?;
+    }
 
     let ctx = minijinja::Value::from_serialize(bindings);
     let mut documents = Vec::new();
 
-    for tmpl in templates {
-        if tmpl.is_template {
-            let name = tmpl.path.file_name().unwrap_or_default().to_string_lossy();
-            let rendered = env
-                .render_str(&tmpl.content, &ctx)
-                .map_err(|e| format!("boom: render error in {name}: {e}"))?;
-            documents.push(rendered);
+    for tmpl in templates.iter().filter(|t| !t.is_partial) {
+        let content = if tmpl.is_template {
+            env.render_named_str(&tmpl.name, &tmpl.content, &ctx)
+                .map_err(|e| format!("boom: render error in {}: {e}", tmpl.name))?
         } else {
-            documents.push(tmpl.content.clone());
-        }
+            tmpl.content.clone()
+        };
+        documents.push(RenderedFile {
+            name: tmpl.name.clone(),
+            content,
+        });
     }
 
-    Ok(documents.join("---\n"))
+    Ok(documents)
 }
 
-pub fn parse_bindings(pairs: &[String]) -> HashMap<String, String> {
-    let mut map = HashMap::new();
+pub fn parse_bindings(pairs: &[String]) -> Result<serde_json::Value, BoomError> {
+    let mut bindings = serde_json::Value::Object(serde_json::Map::new());
     for pair in pairs {
-        if let Some((key, value)) = pair.split_once('=') {
-            map.insert(key.to_string(), value.to_string());
-        } else {
-            eprintln!("boom: malformed binding (missing '='): {pair}");
-        }
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| BoomError::Usage(format!("malformed binding (missing '='): {pair}")))?;
+        let overlay = key.rsplit('.').fold(
+            serde_json::Value::String(value.to_string()),
+            |inner, segment| {
+                let mut map = serde_json::Map::new();
+                map.insert(segment.to_string(), inner);
+                serde_json::Value::Object(map)
+            },
+        );
+        merge(&mut bindings, overlay);
+    }
+    Ok(bindings)
+}
+
+pub fn load_bindings_file(path: &Path) -> Result<serde_json::Value, BoomError> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        BoomError::Render(format!("cannot read bindings file {}: {e}", path.display()))
+    })?;
+    let value: serde_json::Value = serde_yaml::from_str(&content).map_err(|e| {
+        BoomError::Render(format!(
+            "cannot parse bindings file {}: {e}",
+            path.display()
+        ))
+    })?;
+    match value {
+        serde_json::Value::Object(_) => Ok(value),
+        serde_json::Value::Null => Ok(serde_json::Value::Object(serde_json::Map::new())),
+        _ => Err(BoomError::Render(format!(
+            "bindings file {} must contain a mapping",
+            path.display()
+        ))),
+    }
+}
+
+// Later files and `--bindings` overrides win; nested mappings merge key by key.
+pub fn load_bindings(
+    files: &[PathBuf],
+    overrides: &[String],
+) -> Result<serde_json::Value, BoomError> {
+    let mut bindings = serde_json::Value::Object(serde_json::Map::new());
+    for file in files {
+        merge(&mut bindings, load_bindings_file(file)?);
     }
-    map
+    merge(&mut bindings, parse_bindings(overrides)?);
+    Ok(bindings)
 }
 
-pub fn load_bindings_file(path: &Path) -> HashMap<String, String> {
-    let content = fs::read_to_string(path).unwrap_or_else(|e| {
-        eprintln!("boom: cannot read bindings file {}: {e}", path.display());
-        process::exit(1);
-    });
-    let value: serde_yaml::Value = serde_yaml::from_str(&content).unwrap_or_else(|e| {
-        eprintln!("boom: cannot parse bindings file {}: {e}", path.display());
-        process::exit(1);
-    });
-    let mut map = HashMap::new();
-    if let serde_yaml::Value::Mapping(mapping) = value {
-        for (k, v) in mapping {
-            if let (serde_yaml::Value::String(key), serde_yaml::Value::String(val)) = (k, v) {
-                map.insert(key, val);
+pub fn merge(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
             }
         }
+        (base, overlay) => *base = overlay,
     }
-    map
 }
@@ -1,11 +1,13 @@
-use std::process;
+use std::time::Instant;
 
-use kube::Api;
-use kube::api::{ApiResource, DynamicObject, Patch, PatchParams};
+use kube::api::{DynamicObject, Patch, PatchParams};
 use serde_json::json;
 
+use crate::discovery::ApiCatalog;
+use crate::error::BoomError;
 use crate::manifest::ResourceDescriptor;
 use crate::monitor;
+use crate::output;
 
 pub fn build_restart_patch(timestamp: &str) -> serde_json::Value {
     json!({
@@ -21,16 +23,19 @@ pub fn build_restart_patch(timestamp: &str) -> serde_json::Value {
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     client: kube::Client,
+    catalog: &ApiCatalog,
     namespace: &str,
     deployments: &[String],
     statefulsets: &[String],
     daemonsets: &[String],
     verify_result: bool,
     global_timeout: u64,
-) {
-    let timestamp = chrono_now();
+) -> Result<(), BoomError> {
+    let start = Instant::now();
+    let timestamp = chrono_now()?;
     let patch = build_restart_patch(&timestamp);
 
     let kinds = [
@@ -43,81 +48,117 @@ pub async fn run(
 
     for (kind, api_version, names) in &kinds {
         for name in *names {
-            patch_resource(&client, namespace, kind, api_version, name, &patch).await;
-            patched_resources.push(ResourceDescriptor {
-                api_version: (*api_version).to_string(),
-                kind: (*kind).to_string(),
-                name: name.clone(),
-                namespace: Some(namespace.to_string()),
-                raw: serde_yaml::Value::Null,
-            });
+            let live = patch_resource(&client, catalog, namespace, kind, api_version, name, &patch)
+                .await?;
+            patched_resources.push(
+                restarted_descriptor(api_version, kind, namespace, &live)
+                    .map_err(|e| BoomError::Apply(format!("{kind}/{name}: {e}")))?,
+            );
         }
     }
 
-    if verify_result && !patched_resources.is_empty() {
-        let state = monitor::watch_resources(&client, &patched_resources, global_timeout).await;
-        match state {
-            monitor::ResourceState::Ready => {
-                eprintln!("[boom] all restarts complete");
-            }
-            monitor::ResourceState::Failed => {
-                for resource in &patched_resources {
-                    let diag = monitor::collect_diagnostics(&client, resource).await;
-                    if !diag.is_empty() {
-                        eprintln!("{diag}");
-                    }
-                }
-                process::exit(1);
+    if !verify_result || patched_resources.is_empty() {
+        output::summary_table(&summary_rows(&patched_resources, "OK", start));
+        output::finished("succeeded");
+        return Ok(());
+    }
+
+    let state =
+        monitor::watch_resources(&client, catalog, &patched_resources, global_timeout).await;
+    match state {
+        monitor::ResourceState::Ready => {
+            output::info("[boom] all restarts complete");
+            output::summary_table(&summary_rows(&patched_resources, "Ready", start));
+            output::finished("succeeded");
+            Ok(())
+        }
+        monitor::ResourceState::Failed => {
+            for resource in &patched_resources {
+                let diag = monitor::collect_diagnostics(&client, resource).await;
+                output::diagnostics(&format!("{}/{}", resource.kind, resource.name), &diag);
             }
-            monitor::ResourceState::NotReady => {
-                eprintln!("[boom] timed out waiting for restarts to complete");
-                for resource in &patched_resources {
-                    let diag = monitor::collect_diagnostics(&client, resource).await;
-                    if !diag.is_empty() {
-                        eprintln!("{diag}");
-                    }
-                }
-                process::exit(70);
+            output::summary_table(&summary_rows(&patched_resources, "Failed", start));
+            output::finished("failed");
+            Err(BoomError::Failed("restarts failed".to_string()))
+        }
+        monitor::ResourceState::NotReady => {
+            output::warn("[boom] timed out waiting for restarts to complete");
+            for resource in &patched_resources {
+                let diag = monitor::collect_diagnostics(&client, resource).await;
+                output::diagnostics(&format!("{}/{}", resource.kind, resource.name), &diag);
             }
+            output::summary_table(&summary_rows(&patched_resources, "Timeout", start));
+            output::finished("timeout");
+            Err(BoomError::Timeout(format!(
+                "restarts not complete after {global_timeout}s"
+            )))
         }
     }
 }
 
+// Built from the object the patch returned, so readiness checks see its
+// spec.selector and can follow the restarted pods.
+pub fn restarted_descriptor(
+    api_version: &str,
+    kind: &str,
+    namespace: &str,
+    live: &DynamicObject,
+) -> Result<ResourceDescriptor, String> {
+    let raw = serde_yaml::to_value(live).map_err(|e| format!("failed to convert object: {e}"))?;
+    Ok(ResourceDescriptor {
+        api_version: api_version.to_string(),
+        kind: kind.to_string(),
+        name: live.metadata.name.clone().unwrap_or_default(),
+        namespace: Some(namespace.to_string()),
+        source: String::new(),
+        raw,
+    })
+}
+
+fn summary_rows(
+    resources: &[ResourceDescriptor],
+    status: &str,
+    start: Instant,
+) -> Vec<(String, String, String, String)> {
+    let duration = format!("{:.1}s", start.elapsed().as_secs_f64());
+    resources
+        .iter()
+        .map(|r| {
+            (
+                r.name.clone(),
+                r.kind.clone(),
+                status.to_string(),
+                duration.clone(),
+            )
+        })
+        .collect()
+}
+
 async fn patch_resource(
     client: &kube::Client,
+    catalog: &ApiCatalog,
     namespace: &str,
     kind: &str,
     api_version: &str,
     name: &str,
     patch: &serde_json::Value,
-) {
-    let (group, version) = crate::deploy::parse_api_version(api_version);
-    let ar = ApiResource {
-        group: group.to_string(),
-        version: version.to_string(),
-        api_version: api_version.to_string(),
-        kind: kind.to_string(),
-        plural: crate::deploy::pluralize(kind),
-    };
-
-    let api: Api<DynamicObject> = Api::namespaced_with(client.clone(), namespace, &ar);
+) -> Result<DynamicObject, BoomError> {
+    let api = catalog
+        .api(client, api_version, kind, namespace)
+        .map_err(|e| BoomError::Apply(format!("failed to patch {kind}/{name}: {e}")))?;
     let params = PatchParams::apply("boom");
-    match api.patch(name, &params, &Patch::Merge(patch.clone())).await {
-        Ok(_) => eprintln!("[boom] patched {kind}/{name}"),
-        Err(e) => {
-            eprintln!("boom: failed to patch {kind}/{name}: {e}");
-            process::exit(1);
-        }
-    }
+    let live = api
+        .patch(name, &params, &Patch::Merge(patch.clone()))
+        .await
+        .map_err(|e| BoomError::Apply(format!("failed to patch {kind}/{name}: {e}")))?;
+    output::applied(kind, name, None);
+    Ok(live)
 }
 
-fn chrono_now() -> String {
+fn chrono_now() -> Result<String, BoomError> {
     let output = std::process::Command::new("date")
         .args(["-u", "+%Y-%m-%dT%H:%M:%SZ"])
         .output()
-        .unwrap_or_else(|e| {
-            eprintln!("boom: failed to get current time: {e}");
-            process::exit(1);
-        });
-    String::from_utf8_lossy(&output.stdout).trim().to_string()
+        .map_err(|e| BoomError::Apply(format!("failed to get current time: {e}")))?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
@@ -0,0 +1,237 @@
+use std::time::Instant;
+
+use k8s_openapi::api::apps::v1::{
+    ControllerRevision, DaemonSet, Deployment, ReplicaSet, StatefulSet,
+};
+use k8s_openapi::api::core::v1::PodTemplateSpec;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::Api;
+use kube::api::{ListParams, Patch, PatchParams};
+use serde_json::json;
+
+use crate::discovery::ApiCatalog;
+use crate::manifest::ResourceDescriptor;
+use crate::monitor::{self, ResourceState};
+use crate::output;
+
+pub const REVISION_ANNOTATION: &str = "deployment.kubernetes.io/revision";
+
+pub fn is_revertible(kind: &str) -> bool {
+    matches!(kind, "Deployment" | "StatefulSet" | "DaemonSet")
+}
+
+fn owned_by(meta: &ObjectMeta, uid: &str) -> bool {
+    meta.owner_references
+        .as_ref()
+        .is_some_and(|refs| refs.iter().any(|r| r.uid == uid))
+}
+
+pub fn replicaset_revision(meta: &ObjectMeta) -> Option<i64> {
+    meta.annotations
+        .as_ref()?
+        .get(REVISION_ANNOTATION)?
+        .parse()
+        .ok()
+}
+
+pub fn previous_replicaset<'a>(
+    current: i64,
+    uid: &str,
+    replicasets: &'a [ReplicaSet],
+) -> Option<&'a ReplicaSet> {
+    replicasets
+        .iter()
+        .filter(|rs| owned_by(&rs.metadata, uid))
+        .filter_map(|rs| Some((replicaset_revision(&rs.metadata)?, rs)))
+        .filter(|(revision, _)| *revision < current)
+        .max_by_key(|(revision, _)| *revision)
+        .map(|(_, rs)| rs)
+}
+
+pub fn previous_controller_revision<'a>(
+    current: i64,
+    uid: &str,
+    revisions: &'a [ControllerRevision],
+) -> Option<&'a ControllerRevision> {
+    revisions
+        .iter()
+        .filter(|cr| owned_by(&cr.metadata, uid) && cr.revision < current)
+        .max_by_key(|cr| cr.revision)
+}
+
+// Replaces the whole pod template, dropping the controller-injected hash label.
+pub fn template_patch(template: &PodTemplateSpec) -> serde_json::Value {
+    let mut template = serde_json::to_value(template).unwrap_or_default();
+    if let Some(labels) = template
+        .pointer_mut("/metadata/labels")
+        .and_then(|l| l.as_object_mut())
+    {
+        labels.remove("pod-template-hash");
+    }
+    if let Some(obj) = template.as_object_mut() {
+        obj.insert("$patch".to_string(), json!("replace"));
+    }
+    json!({ "spec": { "template": template } })
+}
+
+fn patch_params() -> PatchParams {
+    PatchParams {
+        field_manager: Some("boom".to_string()),
+        ..Default::default()
+    }
+}
+
+async fn revert_deployment(client: &kube::Client, ns: &str, name: &str) -> Result<(), String> {
+    let api: Api<Deployment> = Api::namespaced(client.clone(), ns);
+    let deployment = api.get(name).await.map_err(|e| e.to_string())?;
+    let uid = deployment.metadata.uid.clone().unwrap_or_default();
+    let current = replicaset_revision(&deployment.metadata).unwrap_or(i64::MAX);
+
+    let rs_api: Api<ReplicaSet> = Api::namespaced(client.clone(), ns);
+    let replicasets = rs_api
+        .list(&ListParams::default())
+        .await
+        .map_err(|e| e.to_string())?
+        .items;
+    let previous =
+        previous_replicaset(current, &uid, &replicasets).ok_or("no previous revision")?;
+    let template = previous
+        .spec
+        .as_ref()
+        .and_then(|s| s.template.as_ref())
+        .ok_or("previous ReplicaSet has no pod template")?;
+
+    api.patch(
+        name,
+        &patch_params(),
+        &Patch::Strategic(template_patch(template)),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn revert_from_controller_revision<K>(
+    client: &kube::Client,
+    ns: &str,
+    name: &str,
+    current_revision_name: impl Fn(&K) -> Option<String>,
+) -> Result<(), String>
+where
+    K: kube::Resource<Scope = k8s_openapi::NamespaceResourceScope, DynamicType = ()>
+        + Clone
+        + serde::de::DeserializeOwned
+        + std::fmt::Debug,
+{
+    let api: Api<K> = Api::namespaced(client.clone(), ns);
+    let workload = api.get(name).await.map_err(|e| e.to_string())?;
+    let uid = workload.meta().uid.clone().unwrap_or_default();
+
+    let cr_api: Api<ControllerRevision> = Api::namespaced(client.clone(), ns);
+    let revisions = cr_api
+        .list(&ListParams::default())
+        .await
+        .map_err(|e| e.to_string())?
+        .items;
+
+    let owned = revisions.iter().filter(|cr| owned_by(&cr.metadata, &uid));
+    let current = match current_revision_name(&workload) {
+        Some(current) => owned
+            .into_iter()
+            .find(|cr| cr.metadata.name.as_deref() == Some(current.as_str()))
+            .map(|cr| cr.revision),
+        None => owned.map(|cr| cr.revision).max(),
+    }
+    .unwrap_or(i64::MAX);
+
+    let previous =
+        previous_controller_revision(current, &uid, &revisions).ok_or("no previous revision")?;
+    let data = previous
+        .data
+        .as_ref()
+        .ok_or("previous ControllerRevision has no data")?;
+
+    api.patch(name, &patch_params(), &Patch::Strategic(data.0.clone()))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub async fn revert_workload(
+    client: &kube::Client,
+    resource: &ResourceDescriptor,
+) -> Result<(), String> {
+    let ns = resource.namespace.as_deref().unwrap_or("default");
+    match resource.kind.as_str() {
+        "Deployment" => revert_deployment(client, ns, &resource.name).await,
+        "StatefulSet" => {
+            revert_from_controller_revision::<StatefulSet>(client, ns, &resource.name, |sts| {
+                sts.status.as_ref()?.update_revision.clone()
+            })
+            .await
+        }
+        "DaemonSet" => {
+            revert_from_controller_revision::<DaemonSet>(client, ns, &resource.name, |_| None).await
+        }
+        kind => Err(format!("{kind} does not support rollback")),
+    }
+}
+
+// Reverts every workload that isn't ready and waits for the reverted set to
+// settle. Returns summary rows describing each rollback.
+pub async fn revert_failed(
+    client: &kube::Client,
+    catalog: &ApiCatalog,
+    resources: &[ResourceDescriptor],
+    timeout_secs: u64,
+) -> Vec<(String, String, String, String)> {
+    let start = Instant::now();
+    let mut rows = Vec::new();
+    let mut reverted = Vec::new();
+
+    for resource in resources.iter().filter(|r| is_revertible(&r.kind)) {
+        if monitor::check_resource(client, catalog, resource).await == ResourceState::Ready {
+            continue;
+        }
+        let key = format!("{}/{}", resource.kind, resource.name);
+        match revert_workload(client, resource).await {
+            Ok(()) => {
+                output::warn(&format!(
+                    "[boom] rolled back {key} to its previous revision"
+                ));
+                reverted.push(resource.clone());
+            }
+            Err(e) => {
+                output::error(&format!("[boom] rollback of {key} failed: {e}"));
+                rows.push((
+                    resource.name.clone(),
+                    resource.kind.clone(),
+                    "RollbackFailed".to_string(),
+                    format!("{:.1}s", start.elapsed().as_secs_f64()),
+                ));
+            }
+        }
+    }
+
+    if reverted.is_empty() {
+        return rows;
+    }
+
+    let state = monitor::watch_resources(client, catalog, &reverted, timeout_secs).await;
+    let status = if state == ResourceState::Ready {
+        output::success("[boom] rollback complete");
+        "RolledBack"
+    } else {
+        output::error(&format!("[boom] rolled back workloads are {state}"));
+        "RollbackFailed"
+    };
+    for resource in &reverted {
+        rows.push((
+            resource.name.clone(),
+            resource.kind.clone(),
+            status.to_string(),
+            format!("{:.1}s", start.elapsed().as_secs_f64()),
+        ));
+    }
+    rows
+}
@@ -0,0 +1,54 @@
+use crate::deploy::{self, DeployOptions};
+use crate::discovery::ApiCatalog;
+use crate::error::BoomError;
+use crate::history;
+use crate::output;
+
+pub async fn run(
+    client: kube::Client,
+    namespace: &str,
+    owner: &str,
+    revision: Option<u32>,
+    options: &DeployOptions,
+) -> Result<(), BoomError> {
+    let revisions = history::list_revisions(&client, namespace, owner)
+        .await
+        .map_err(BoomError::Apply)?;
+    let target = history::rollback_target(&revisions, revision)
+        .map_err(|e| BoomError::Usage(format!("cannot roll back {owner}: {e}")))?;
+    let resources = history::load(&client, namespace, owner, target)
+        .await
+        .map_err(BoomError::Apply)?;
+
+    let mut catalog = ApiCatalog::discover(&client)
+        .await
+        .map_err(BoomError::Auth)?;
+    catalog.register_crds(&resources);
+
+    output::info(&format!("[boom] rolling back {owner} to revision {target}"));
+    let result = deploy::run(
+        client.clone(),
+        &catalog,
+        namespace,
+        resources.clone(),
+        options,
+    )
+    .await;
+    if let Err(e) = result {
+        if let Err(record_err) = history::record(&client, namespace, owner, &resources, false).await
+        {
+            output::warn(&format!(
+                "[boom] failed to record deploy history: {record_err}"
+            ));
+        }
+        return Err(e);
+    }
+
+    match history::record(&client, namespace, owner, &resources, true).await {
+        Ok(recorded) => output::success(&format!(
+            "[boom] rolled back to revision {target} (recorded as revision {recorded})"
+        )),
+        Err(e) => output::warn(&format!("[boom] rollback applied but not recorded: {e}")),
+    }
+    Ok(())
+}
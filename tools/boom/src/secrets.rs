@@ -0,0 +1,190 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use crypto_box::aead::Aead;
+use crypto_box::{Nonce, PublicKey, SalsaBox, SecretKey};
+
+pub const DEFAULT_KEYDIR: &str = "/opt/ejson/keys";
+pub const REDACTED: &str = "[REDACTED]";
+
+// EJSON (https://github.com/Shopify/ejson): a JSON document with a `_public_key`
+// and string values of the form `EJ[1:<ephemeral pk>:<nonce>:<box>]`.
+pub fn load_secrets_file(
+    path: &Path,
+    key_file: Option<&Path>,
+) -> Result<serde_json::Value, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("cannot read secrets file {}: {e}", path.display()))?;
+    let doc: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("cannot parse secrets file {}: {e}", path.display()))?;
+
+    let public_key = doc
+        .get("_public_key")
+        .and_then(serde_json::Value::as_str)
+        .ok_or_else(|| format!("secrets file {} has no _public_key", path.display()))?;
+
+    let key_path = match key_file {
+        Some(key_file) => key_file.to_path_buf(),
+        None => default_key_path(public_key),
+    };
+    let secret_key = read_secret_key(&key_path)?;
+
+    decrypt_document(&doc, &secret_key).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn default_key_path(public_key: &str) -> PathBuf {
+    let keydir = std::env::var("EJSON_KEYDIR").unwrap_or_else(|_| DEFAULT_KEYDIR.to_string());
+    Path::new(&keydir).join(public_key)
+}
+
+fn read_secret_key(path: &Path) -> Result<SecretKey, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("cannot read secrets key {}: {e}", path.display()))?;
+    let bytes = hex::decode(content.trim())
+        .map_err(|e| format!("invalid secrets key {}: {e}", path.display()))?;
+    SecretKey::from_slice(&bytes)
+        .map_err(|_| format!("invalid secrets key {}: expected 32 bytes", path.display()))
+}
+
+pub fn decrypt_document(
+    doc: &serde_json::Value,
+    secret_key: &SecretKey,
+) -> Result<serde_json::Value, String> {
+    match doc {
+        serde_json::Value::Object(map) => {
+            let mut out = serde_json::Map::new();
+            for (key, value) in map {
+                if key == "_public_key" {
+                    continue;
+                }
+                let value =
+                    decrypt_document(value, secret_key).map_err(|e| format!("{key}: {e}"))?;
+                out.insert(key.clone(), value);
+            }
+            Ok(serde_json::Value::Object(out))
+        }
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(|item| decrypt_document(item, secret_key))
+            .collect::<Result<Vec<_>, _>>()
+            .map(serde_json::Value::Array),
+        serde_json::Value::String(s) if s.starts_with("EJ[") => {
+            decrypt_value(s, secret_key).map(serde_json::Value::String)
+        }
+        other => Ok(other.clone()),
+    }
+}
+
+pub fn decrypt_value(value: &str, secret_key: &SecretKey) -> Result<String, String> {
+    let inner = value
+        .strip_prefix("EJ[")
+        .and_then(|v| v.strip_suffix(']'))
+        .ok_or("malformed encrypted value")?;
+    let parts: Vec<&str> = inner.split(':').collect();
+    let [version, encrypter, nonce, boxed] = parts.as_slice() else {
+        return Err("malformed encrypted value".to_string());
+    };
+    if *version != "1" {
+        return Err(format!("unsupported encrypted value version {version}"));
+    }
+
+    let decode = |part: &str| {
+        STANDARD
+            .decode(part)
+            .map_err(|e| format!("malformed encrypted value: {e}"))
+    };
+    let encrypter = PublicKey::from_slice(&decode(encrypter)?)
+        .map_err(|_| "malformed encrypted value: bad public key".to_string())?;
+    let nonce = decode(nonce)?;
+    if nonce.len() != 24 {
+        return Err("malformed encrypted value: bad nonce".to_string());
+    }
+
+    let plaintext = SalsaBox::new(&encrypter, secret_key)
+        .decrypt(Nonce::from_slice(&nonce), decode(boxed)?.as_slice())
+        .map_err(|_| "decryption failed (wrong key?)".to_string())?;
+    String::from_utf8(plaintext).map_err(|e| format!("decrypted value is not UTF-8: {e}"))
+}
+
+// The values worth redacting: EJSON leaves `_`-prefixed keys unencrypted, so
+// their values aren't secret.
+pub fn secret_strings(value: &serde_json::Value) -> Vec<String> {
+    let mut out = Vec::new();
+    collect_strings(value, &mut out);
+    out
+}
+
+fn collect_strings(value: &serde_json::Value, out: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Object(map) => map
+            .iter()
+            .filter(|(key, v)| !(key.starts_with('_') && v.is_string()))
+            .for_each(|(_, v)| collect_strings(v, out)),
+        serde_json::Value::Array(items) => items.iter().for_each(|v| collect_strings(v, out)),
+        serde_json::Value::String(s) => out.push(s.clone()),
+        _ => {}
+    }
+}
+
+// Masks secret values and their base64 encodings wherever one makes up a whole
+// YAML scalar: a mapping value, a sequence item or one line of a block scalar
+// (multi-line secrets are matched line by line). Matching whole scalars rather
+// than substrings keeps short secrets from mangling unrelated text.
+pub fn redact(text: &str, secrets: &[String]) -> String {
+    let encoded: Vec<String> = secrets.iter().map(|s| STANDARD.encode(s)).collect();
+    let needles: HashSet<&str> = secrets
+        .iter()
+        .flat_map(|s| s.lines())
+        .chain(encoded.iter().map(String::as_str))
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .collect();
+
+    text.split_inclusive('\n')
+        .map(|line| redact_line(line, &needles))
+        .collect()
+}
+
+// Works on both rendered YAML and unified diff lines, whose first character is
+// a `+`, `-` or space marker.
+fn redact_line(line: &str, needles: &HashSet<&str>) -> String {
+    let content = line.trim_end_matches(['\r', '\n']);
+    let ending = &line[content.len()..];
+
+    let mut starts = vec![0];
+    let unmarked = match content.strip_prefix(['+', '-', ' ']) {
+        Some(unmarked) => {
+            starts.push(1);
+            unmarked
+        }
+        None => content,
+    };
+    let body = content.len() - unmarked.trim_start().len();
+    if content[body..].starts_with("- ") {
+        starts.push(body + 2);
+    }
+    if let Some(colon) = content.find(": ") {
+        starts.push(colon + 2);
+    }
+
+    for start in starts {
+        let rest = &content[start..];
+        if needles.contains(unquote(rest.trim())) {
+            let indent = rest.len() - rest.trim_start().len();
+            return format!("{}{REDACTED}{ending}", &content[..start + indent]);
+        }
+    }
+    line.to_string()
+}
+
+fn unquote(scalar: &str) -> &str {
+    for quote in ['"', '\''] {
+        if scalar.len() >= 2 && scalar.starts_with(quote) && scalar.ends_with(quote) {
+            return &scalar[1..scalar.len() - 1];
+        }
+    }
+    scalar
+}
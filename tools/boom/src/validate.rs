@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use k8s_openapi::api::admissionregistration::v1 as admissionregistration;
+use k8s_openapi::api::apps::v1 as apps;
+use k8s_openapi::api::autoscaling::{v1 as autoscaling_v1, v2 as autoscaling_v2};
+use k8s_openapi::api::batch::v1 as batch;
+use k8s_openapi::api::core::v1 as core;
+use k8s_openapi::api::networking::v1 as networking;
+use k8s_openapi::api::policy::v1 as policy;
+use k8s_openapi::api::rbac::v1 as rbac;
+use k8s_openapi::api::scheduling::v1 as scheduling;
+use k8s_openapi::api::storage::v1 as storage;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::Api;
+use kube::api::ListParams;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::error::BoomError;
+use crate::manifest::{self, ResourceDescriptor};
+use crate::output;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub source: String,
+    pub resource: String,
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: {}: {}",
+            self.source, self.resource, self.path, self.message
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub errors: Vec<ValidationError>,
+    // Resources with neither a built-in nor a CRD schema, as `Kind/name`.
+    pub unchecked: Vec<String>,
+}
+
+// openAPIV3Schema of every known CRD version, keyed by (apiVersion, kind).
+#[derive(Debug, Default)]
+pub struct SchemaStore {
+    crds: HashMap<(String, String), Value>,
+}
+
+impl SchemaStore {
+    pub fn register_crd(&mut self, crd: &Value) {
+        let spec = &crd["spec"];
+        let (Some(group), Some(kind)) = (spec["group"].as_str(), spec["names"]["kind"].as_str())
+        else {
+            return;
+        };
+        for version in spec["versions"].as_array().into_iter().flatten() {
+            let (Some(name), Some(schema)) = (
+                version["name"].as_str(),
+                version["schema"].get("openAPIV3Schema"),
+            ) else {
+                continue;
+            };
+            self.crds.insert(
+                (format!("{group}/{name}"), kind.to_string()),
+                schema.clone(),
+            );
+        }
+    }
+
+    pub fn register_crds(&mut self, resources: &[ResourceDescriptor]) {
+        for crd in resources
+            .iter()
+            .filter(|r| r.kind == "CustomResourceDefinition")
+        {
+            if let Ok(value) = serde_json::to_value(&crd.raw) {
+                self.register_crd(&value);
+            }
+        }
+    }
+
+    // Reads CRDs from every .yml/.yaml file under `dir`; other documents are ignored.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<(), String> {
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| format!("cannot read CRD directory {}: {e}", dir.display()))?;
+        let mut paths: Vec<_> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+        paths.sort();
+
+        for path in paths {
+            if path.is_dir() {
+                self.load_dir(&path)?;
+                continue;
+            }
+            let is_yaml = path
+                .extension()
+                .is_some_and(|ext| ext == "yml" || ext == "yaml");
+            if !is_yaml {
+                continue;
+            }
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+            let resources = manifest::parse_source(&path.display().to_string(), &content)?;
+            self.register_crds(&resources);
+        }
+        Ok(())
+    }
+
+    pub async fn fetch_crds(&mut self, client: &kube::Client) -> Result<(), String> {
+        let api: Api<CustomResourceDefinition> = Api::all(client.clone());
+        let crds = api
+            .list(&ListParams::default())
+            .await
+            .map_err(|e| format!("failed to list CRDs: {e}"))?;
+        for crd in crds.items {
+            if let Ok(value) = serde_json::to_value(&crd) {
+                self.register_crd(&value);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn crd_schema(&self, api_version: &str, kind: &str) -> Option<&Value> {
+        self.crds.get(&(api_version.to_string(), kind.to_string()))
+    }
+}
+
+// Field path and message, e.g. ("spec.replicas", "invalid type: ...").
+type FieldError = (String, String);
+
+fn join_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{parent}.{key}")
+    }
+}
+
+fn ignored_path(path: &serde_ignored::Path) -> String {
+    match path {
+        serde_ignored::Path::Root => String::new(),
+        serde_ignored::Path::Seq { parent, index } => format!("{}[{index}]", ignored_path(parent)),
+        serde_ignored::Path::Map { parent, key } => join_path(&ignored_path(parent), key),
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => ignored_path(parent),
+    }
+}
+
+// Deserializes into the generated k8s-openapi type: fields the type doesn't know
+// are reported as unknown, and type mismatches carry the path where they happened.
+fn check_typed<T: DeserializeOwned>(value: &Value, prefix: &str) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+    let mut unknown = |path: serde_ignored::Path| {
+        errors.push((
+            join_path(prefix, &ignored_path(&path)),
+            "unknown field".to_string(),
+        ));
+    };
+    let result: Result<T, _> =
+        serde_path_to_error::deserialize(serde_ignored::Deserializer::new(value, &mut unknown));
+    if let Err(e) = result {
+        let path = e.path().to_string();
+        let path = if path == "." {
+            prefix.to_string()
+        } else {
+            join_path(prefix, &path)
+        };
+        errors.push((path, e.into_inner().to_string()));
+    }
+    errors
+}
+
+// Maps of resource name to Quantity (container resources, quotas, limit ranges,
+// PVC capacity) and scalar Quantity fields (emptyDir sizeLimit, HPA targets).
+const QUANTITY_MAPS: &[&str] = &[
+    "limits",
+    "requests",
+    "hard",
+    "capacity",
+    "allocatable",
+    "overhead",
+    "max",
+    "min",
+    "default",
+    "defaultRequest",
+    "maxLimitRequestRatio",
+];
+const TARGET_QUANTITY_FIELDS: &[&str] = &["value", "averageValue"];
+
+// The API server accepts numeric quantities (`cpu: 1`), but k8s-openapi's
+// Quantity only deserializes from a string.
+fn stringify_quantities(value: &mut Value, parent: &str) {
+    match value {
+        Value::Object(fields) => {
+            for (key, child) in fields.iter_mut() {
+                let is_quantity = QUANTITY_MAPS.contains(&parent)
+                    || key == "sizeLimit"
+                    || (parent == "target" && TARGET_QUANTITY_FIELDS.contains(&key.as_str()));
+                if is_quantity && child.is_number() {
+                    *child = Value::String(child.to_string());
+                } else {
+                    stringify_quantities(child, key);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                stringify_quantities(item, parent);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_builtin(api_version: &str, kind: &str, value: &Value) -> Option<Vec<FieldError>> {
+    let mut value = value.clone();
+    stringify_quantities(&mut value, "");
+    let value = &value;
+    let errors = match (api_version, kind) {
+        ("v1", "ConfigMap") => check_typed::<core::ConfigMap>(value, ""),
+        ("v1", "Endpoints") => check_typed::<core::Endpoints>(value, ""),
+        ("v1", "LimitRange") => check_typed::<core::LimitRange>(value, ""),
+        ("v1", "Namespace") => check_typed::<core::Namespace>(value, ""),
+        ("v1", "PersistentVolume") => check_typed::<core::PersistentVolume>(value, ""),
+        ("v1", "PersistentVolumeClaim") => check_typed::<core::PersistentVolumeClaim>(value, ""),
+        ("v1", "Pod") => check_typed::<core::Pod>(value, ""),
+        ("v1", "ResourceQuota") => check_typed::<core::ResourceQuota>(value, ""),
+        ("v1", "Secret") => check_typed::<core::Secret>(value, ""),
+        ("v1", "Service") => check_typed::<core::Service>(value, ""),
+        ("v1", "ServiceAccount") => check_typed::<core::ServiceAccount>(value, ""),
+        ("apps/v1", "DaemonSet") => check_typed::<apps::DaemonSet>(value, ""),
+        ("apps/v1", "Deployment") => check_typed::<apps::Deployment>(value, ""),
+        ("apps/v1", "ReplicaSet") => check_typed::<apps::ReplicaSet>(value, ""),
+        ("apps/v1", "StatefulSet") => check_typed::<apps::StatefulSet>(value, ""),
+        ("batch/v1", "CronJob") => check_typed::<batch::CronJob>(value, ""),
+        ("batch/v1", "Job") => check_typed::<batch::Job>(value, ""),
+        ("autoscaling/v1", "HorizontalPodAutoscaler") => {
+            check_typed::<autoscaling_v1::HorizontalPodAutoscaler>(value, "")
+        }
+        ("autoscaling/v2", "HorizontalPodAutoscaler") => {
+            check_typed::<autoscaling_v2::HorizontalPodAutoscaler>(value, "")
+        }
+        ("networking.k8s.io/v1", "Ingress") => check_typed::<networking::Ingress>(value, ""),
+        ("networking.k8s.io/v1", "IngressClass") => {
+            check_typed::<networking::IngressClass>(value, "")
+        }
+        ("networking.k8s.io/v1", "NetworkPolicy") => {
+            check_typed::<networking::NetworkPolicy>(value, "")
+        }
+        ("policy/v1", "PodDisruptionBudget") => {
+            check_typed::<policy::PodDisruptionBudget>(value, "")
+        }
+        ("rbac.authorization.k8s.io/v1", "ClusterRole") => {
+            check_typed::<rbac::ClusterRole>(value, "")
+        }
+        ("rbac.authorization.k8s.io/v1", "ClusterRoleBinding") => {
+            check_typed::<rbac::ClusterRoleBinding>(value, "")
+        }
+        ("rbac.authorization.k8s.io/v1", "Role") => check_typed::<rbac::Role>(value, ""),
+        ("rbac.authorization.k8s.io/v1", "RoleBinding") => {
+            check_typed::<rbac::RoleBinding>(value, "")
+        }
+        ("scheduling.k8s.io/v1", "PriorityClass") => {
+            check_typed::<scheduling::PriorityClass>(value, "")
+        }
+        ("storage.k8s.io/v1", "StorageClass") => check_typed::<storage::StorageClass>(value, ""),
+        ("admissionregistration.k8s.io/v1", "MutatingWebhookConfiguration") => {
+            check_typed::<admissionregistration::MutatingWebhookConfiguration>(value, "")
+        }
+        ("admissionregistration.k8s.io/v1", "ValidatingWebhookConfiguration") => {
+            check_typed::<admissionregistration::ValidatingWebhookConfiguration>(value, "")
+        }
+        ("apiextensions.k8s.io/v1", "CustomResourceDefinition") => {
+            check_typed::<CustomResourceDefinition>(value, "")
+        }
+        _ => return None,
+    };
+    Some(errors)
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        _ => true,
+    }
+}
+
+// Checks a value against the structural-schema subset CRDs use: type, properties,
+// required, additionalProperties, items, enum and the x-kubernetes-* escapes.
+pub fn check_schema(schema: &Value, value: &Value, path: &str, errors: &mut Vec<FieldError>) {
+    let at = |p: &str| {
+        if p.is_empty() {
+            ".".to_string()
+        } else {
+            p.to_string()
+        }
+    };
+
+    if value.is_null() && schema["nullable"].as_bool() == Some(true) {
+        return;
+    }
+    if schema["x-kubernetes-int-or-string"].as_bool() == Some(true) {
+        if !(value.is_string() || value.is_i64() || value.is_u64()) {
+            errors.push((at(path), "expected an integer or string".to_string()));
+        }
+        return;
+    }
+    if let Some(expected) = schema["type"].as_str()
+        && !type_matches(expected, value)
+    {
+        errors.push((at(path), format!("expected {expected}")));
+        return;
+    }
+    if let Some(allowed) = schema["enum"].as_array()
+        && !allowed.contains(value)
+    {
+        let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+        errors.push((
+            at(path),
+            format!("{value} is not one of {}", allowed.join(", ")),
+        ));
+    }
+
+    if let Some(items) = value.as_array() {
+        if let Some(item_schema) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                check_schema(item_schema, item, &format!("{path}[{i}]"), errors);
+            }
+        }
+        return;
+    }
+
+    let Some(fields) = value.as_object() else {
+        return;
+    };
+    for required in schema["required"].as_array().into_iter().flatten() {
+        if let Some(name) = required.as_str()
+            && !fields.contains_key(name)
+        {
+            errors.push((join_path(path, name), "missing required field".to_string()));
+        }
+    }
+
+    let properties = schema["properties"].as_object();
+    let preserve = schema["x-kubernetes-preserve-unknown-fields"].as_bool() == Some(true);
+    let additional = schema.get("additionalProperties");
+    if properties.is_none() && additional.is_none() && (preserve || schema["type"].is_null()) {
+        return;
+    }
+    for (key, field) in fields {
+        let field_path = join_path(path, key);
+        if let Some(field_schema) = properties.and_then(|p| p.get(key)) {
+            check_schema(field_schema, field, &field_path, errors);
+        } else if let Some(extra) = additional.filter(|a| a.is_object()) {
+            check_schema(extra, field, &field_path, errors);
+        } else if !preserve && additional.and_then(Value::as_bool) != Some(true) {
+            errors.push((field_path, "unknown field".to_string()));
+        }
+    }
+}
+
+// Custom resources: metadata is checked as ObjectMeta, everything else against
+// the CRD's schema.
+fn check_custom(schema: &Value, value: &Value) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+    if let Some(metadata) = value.get("metadata") {
+        errors.extend(check_typed::<ObjectMeta>(metadata, "metadata"));
+    }
+    let mut body = value.clone();
+    if let Some(fields) = body.as_object_mut() {
+        for key in ["apiVersion", "kind", "metadata"] {
+            fields.remove(key);
+        }
+    }
+    let mut schema = schema.clone();
+    if let Some(properties) = schema["properties"].as_object_mut() {
+        for key in ["apiVersion", "kind", "metadata"] {
+            properties.remove(key);
+        }
+    }
+    check_schema(&schema, &body, "", &mut errors);
+    errors
+}
+
+// Returns None when there is no schema for the resource's type.
+pub fn validate(
+    resource: &ResourceDescriptor,
+    schemas: &SchemaStore,
+) -> Option<Vec<ValidationError>> {
+    let value = serde_json::to_value(&resource.raw).ok()?;
+    let errors = match check_builtin(&resource.api_version, &resource.kind, &value) {
+        Some(errors) => errors,
+        None => check_custom(
+            schemas.crd_schema(&resource.api_version, &resource.kind)?,
+            &value,
+        ),
+    };
+    Some(
+        errors
+            .into_iter()
+            .map(|(path, message)| ValidationError {
+                source: resource.source.clone(),
+                resource: format!("{}/{}", resource.kind, resource.name),
+                path,
+                message,
+            })
+            .collect(),
+    )
+}
+
+pub fn validate_all(resources: &[ResourceDescriptor], schemas: &SchemaStore) -> Report {
+    let mut report = Report::default();
+    for resource in resources {
+        match validate(resource, schemas) {
+            Some(errors) => report.errors.extend(errors),
+            None => report
+                .unchecked
+                .push(format!("{}/{}", resource.kind, resource.name)),
+        }
+    }
+    report
+}
+
+// Pre-apply check used by deploy: CRD schemas come from the manifests and the
+// cluster, and any error aborts before the first resource is applied.
+pub async fn check(
+    client: &kube::Client,
+    resources: &[ResourceDescriptor],
+) -> Result<(), BoomError> {
+    let mut schemas = SchemaStore::default();
+    if let Err(e) = schemas.fetch_crds(client).await {
+        output::warn(&format!(
+            "[boom] {e}; custom resources will not be validated"
+        ));
+    }
+    schemas.register_crds(resources);
+    report_errors(&validate_all(resources, &schemas))
+}
+
+pub fn report_errors(report: &Report) -> Result<(), BoomError> {
+    if report.errors.is_empty() {
+        return Ok(());
+    }
+    for error in &report.errors {
+        output::invalid(&error.source, &error.resource, &error.path, &error.message);
+    }
+    Err(BoomError::Render(format!(
+        "{} manifest validation errors",
+        report.errors.len()
+    )))
+}
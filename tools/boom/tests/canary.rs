@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use boom::canary::{TRACK_LABEL, canary_copy, canary_replicas, plan};
+use boom::deploy::prepare;
+use boom::discovery::ApiCatalog;
+use boom::manifest::parse_manifests;
+
+const DEPLOYMENT: &str = "\
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  annotations:
+    boom.io/canary: \"25%\"
+    boom.io/canary-soak: 2m
+    boom.io/canary-verify: smoke
+spec:
+  replicas: 10
+  selector:
+    matchLabels:
+      app: web
+  template:
+    metadata:
+      labels:
+        app: web
+    spec:
+      containers:
+        - name: web
+          image: web:2.0
+---
+apiVersion: batch/v1
+kind: Job
+metadata:
+  name: smoke
+spec:
+  template:
+    spec:
+      restartPolicy: Never
+      containers:
+        - name: smoke
+          image: smoke:1.0
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: web
+spec:
+  selector:
+    app: web
+";
+
+#[test]
+fn replicas_absolute_and_percentage() {
+    assert_eq!(canary_replicas("2", 10), Ok(2));
+    assert_eq!(canary_replicas("10%", 10), Ok(1));
+    assert_eq!(canary_replicas("25%", 10), Ok(3));
+    assert_eq!(canary_replicas("1%", 3), Ok(1));
+}
+
+#[test]
+fn replicas_rejects_invalid_values() {
+    assert!(canary_replicas("0", 10).is_err());
+    assert!(canary_replicas("0%", 10).is_err());
+    assert!(canary_replicas("150%", 10).is_err());
+    assert!(canary_replicas("some", 10).is_err());
+}
+
+#[test]
+fn plan_reads_annotations_and_removes_verify_job() {
+    let (plans, remaining) = plan(parse_manifests(DEPLOYMENT).unwrap()).unwrap();
+    assert_eq!(plans.len(), 1);
+    assert_eq!(plans[0].deployment.name, "web");
+    assert_eq!(plans[0].replicas, 3);
+    assert_eq!(plans[0].soak, Duration::from_mins(2));
+    assert_eq!(plans[0].verify.as_ref().unwrap().name, "smoke");
+
+    let kinds: Vec<&str> = remaining.iter().map(|r| r.kind.as_str()).collect();
+    assert_eq!(kinds, ["Deployment", "Service"]);
+}
+
+#[test]
+fn plan_without_canaries_is_a_no_op() {
+    let yaml = DEPLOYMENT.replace("    boom.io/canary", "    example.com/canary");
+    let (plans, remaining) = plan(parse_manifests(&yaml).unwrap()).unwrap();
+    assert!(plans.is_empty());
+    assert_eq!(remaining.len(), 3);
+}
+
+#[test]
+fn plan_rejects_missing_verify_job() {
+    let yaml = DEPLOYMENT.replace("canary-verify: smoke", "canary-verify: missing");
+    let err = plan(parse_manifests(&yaml).unwrap()).unwrap_err();
+    assert!(err.contains("Job/missing"), "{err}");
+}
+
+#[test]
+fn plan_rejects_non_deployments() {
+    let yaml = "apiVersion: apps/v1\nkind: StatefulSet\nmetadata:\n  name: db\n  annotations:\n    boom.io/canary: \"1\"\n";
+    let err = plan(parse_manifests(yaml).unwrap()).unwrap_err();
+    assert!(err.contains("only supported on Deployments"));
+}
+
+#[test]
+fn copy_is_scaled_down_with_track_label() {
+    let deployment = parse_manifests(DEPLOYMENT).unwrap().remove(0);
+    let copy = canary_copy(&deployment, 2);
+
+    assert_eq!(copy.name, "web-canary");
+    assert_eq!(copy.raw["metadata"]["name"], "web-canary");
+    assert_eq!(copy.raw["spec"]["replicas"], 2);
+    assert_eq!(copy.raw["metadata"]["labels"][TRACK_LABEL], "canary");
+    assert_eq!(
+        copy.raw["spec"]["selector"]["matchLabels"][TRACK_LABEL],
+        "canary"
+    );
+    assert_eq!(copy.raw["spec"]["selector"]["matchLabels"]["app"], "web");
+    assert_eq!(
+        copy.raw["spec"]["template"]["metadata"]["labels"][TRACK_LABEL],
+        "canary"
+    );
+    assert!(
+        copy.raw["metadata"]["annotations"]
+            .get("boom.io/canary")
+            .is_none()
+    );
+    // The original is untouched.
+    assert_eq!(deployment.raw["spec"]["replicas"], 10);
+}
+
+#[test]
+fn prepare_matches_what_deploy_applies() {
+    let hook = "apiVersion: batch/v1\nkind: Job\nmetadata:\n  name: migrate\n  annotations:\n    boom.io/hook: pre-deploy\n";
+    let yaml = format!("{DEPLOYMENT}---\n{hook}");
+    let prepared = prepare(
+        &ApiCatalog::default(),
+        "prod",
+        parse_manifests(&yaml).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(prepared.pre_deploy.len(), 1);
+    assert_eq!(prepared.canaries.len(), 1);
+    let names: Vec<_> = prepared.resources.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["web", "web"]);
+    let deployment = prepared
+        .resources
+        .iter()
+        .find(|r| r.kind == "Deployment")
+        .unwrap();
+    assert_eq!(
+        deployment.raw["spec"]["selector"]["matchExpressions"][0]["key"],
+        TRACK_LABEL
+    );
+}
@@ -0,0 +1,132 @@
+use std::sync::Mutex;
+
+use boom::clusters::{ClusterTarget, FanOut, parse_contexts, run, targets};
+use boom::error::BoomError;
+use boom::output;
+
+#[test]
+fn contexts_file_with_bindings() {
+    let yaml = "\
+clusters:
+  - context: prod-us
+    bindings:
+      region: us-east-1
+      replicas: 5
+  - context: prod-eu
+";
+    let clusters = parse_contexts(yaml).unwrap();
+    assert_eq!(clusters.len(), 2);
+    assert_eq!(clusters[0].context, "prod-us");
+    assert_eq!(clusters[0].bindings["region"], "us-east-1");
+    assert_eq!(clusters[0].bindings["replicas"], 5);
+    assert!(clusters[1].bindings.is_empty());
+}
+
+#[test]
+fn contexts_file_rejects_unknown_keys() {
+    assert!(parse_contexts("clusters:\n  - context: a\n    binding: {}\n").is_err());
+    assert!(parse_contexts("contexts: []\n").is_err());
+}
+
+#[test]
+fn targets_combine_flags_and_file() {
+    let file = vec![ClusterTarget::new("c")];
+    let all = targets(&["a".to_string(), "b".to_string()], file).unwrap();
+    let contexts: Vec<&str> = all.iter().map(|t| t.context.as_str()).collect();
+    assert_eq!(contexts, ["a", "b", "c"]);
+}
+
+#[test]
+fn targets_reject_duplicates() {
+    let err = targets(&["a".to_string()], vec![ClusterTarget::new("a")]).unwrap_err();
+    assert!(err.contains("context a"));
+}
+
+fn three() -> Vec<ClusterTarget> {
+    ["a", "b", "c"]
+        .into_iter()
+        .map(ClusterTarget::new)
+        .collect()
+}
+
+fn fake_deploy(
+    target: &ClusterTarget,
+    visited: &Mutex<Vec<String>>,
+) -> std::future::Ready<Result<(), BoomError>> {
+    visited.lock().unwrap().push(target.context.clone());
+    output::summary_table(&[(
+        "web".to_string(),
+        "Deployment".to_string(),
+        "Ready".to_string(),
+        "1.0s".to_string(),
+    )]);
+    std::future::ready(if target.context == "b" {
+        Err(BoomError::Timeout("not ready".to_string()))
+    } else {
+        Ok(())
+    })
+}
+
+#[tokio::test]
+async fn sequential_runs_every_cluster_and_reports_first_failure() {
+    let targets = three();
+    let visited = Mutex::new(Vec::new());
+    let result = run(&targets, FanOut::Sequential { fail_fast: false }, |t| {
+        fake_deploy(t, &visited)
+    })
+    .await;
+    assert_eq!(*visited.lock().unwrap(), ["a", "b", "c"]);
+    assert_eq!(result, Err(BoomError::Timeout("b: not ready".to_string())));
+}
+
+#[tokio::test]
+async fn fail_fast_skips_remaining_clusters() {
+    let targets = three();
+    let visited = Mutex::new(Vec::new());
+    let result = run(&targets, FanOut::Sequential { fail_fast: true }, |t| {
+        fake_deploy(t, &visited)
+    })
+    .await;
+    assert_eq!(*visited.lock().unwrap(), ["a", "b"]);
+    assert_eq!(result.unwrap_err().exit_code(), 70);
+}
+
+#[tokio::test]
+async fn parallel_runs_every_cluster() {
+    let targets = three();
+    let visited = Mutex::new(Vec::new());
+    let result = run(&targets, FanOut::Parallel, |t| fake_deploy(t, &visited)).await;
+    let mut visited = visited.lock().unwrap().clone();
+    visited.sort();
+    assert_eq!(visited, ["a", "b", "c"]);
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn in_cluster_collects_summary_rows() {
+    let ((), rows) = output::in_cluster("prod", async {
+        output::summary_table(&[(
+            "web".to_string(),
+            "Deployment".to_string(),
+            "Ready".to_string(),
+            "1.0s".to_string(),
+        )]);
+    })
+    .await;
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].0, "web");
+}
+
+#[tokio::test]
+async fn in_cluster_tags_events() {
+    let (event, _) = output::in_cluster("prod", async {
+        output::event_json("finished", serde_json::json!({ "result": "succeeded" }))
+    })
+    .await;
+    assert_eq!(event["cluster"], "prod");
+    assert!(
+        output::event_json("finished", serde_json::json!({}))
+            .get("cluster")
+            .is_none()
+    );
+}
@@ -0,0 +1,56 @@
+// Fixtures shared by the integration tests; each test crate uses a subset.
+#![allow(dead_code)]
+
+use std::fmt::Write;
+
+use boom::discovery::ApiCatalog;
+use boom::manifest::{ResourceDescriptor, parse_manifests};
+use kube::api::ApiResource;
+
+pub fn resource(kind: &str, name: &str, annotations: &[(&str, &str)]) -> ResourceDescriptor {
+    let mut yaml = format!("apiVersion: v1\nkind: {kind}\nmetadata:\n  name: {name}\n");
+    if !annotations.is_empty() {
+        yaml.push_str("  annotations:\n");
+        for (key, value) in annotations {
+            let _ = writeln!(yaml, "    {key}: \"{value}\"");
+        }
+    }
+    parse_manifests(&yaml).unwrap().remove(0)
+}
+
+fn api_resource(api_version: &str, kind: &str, plural: &str) -> ApiResource {
+    let (group, version) = api_version.rsplit_once('/').unwrap_or(("", api_version));
+    ApiResource {
+        group: group.to_string(),
+        version: version.to_string(),
+        api_version: api_version.to_string(),
+        kind: kind.to_string(),
+        plural: plural.to_string(),
+    }
+}
+
+// A few cluster-scoped and namespaced kinds, as discovery would report them.
+pub fn catalog() -> ApiCatalog {
+    let mut catalog = ApiCatalog::default();
+    catalog.insert(api_resource("v1", "Namespace", "namespaces"), false);
+    catalog.insert(
+        api_resource(
+            "rbac.authorization.k8s.io/v1",
+            "ClusterRole",
+            "clusterroles",
+        ),
+        false,
+    );
+    catalog.insert(
+        api_resource(
+            "apiextensions.k8s.io/v1",
+            "CustomResourceDefinition",
+            "customresourcedefinitions",
+        ),
+        false,
+    );
+    catalog.insert(api_resource("apps/v1", "Deployment", "deployments"), true);
+    catalog.insert(api_resource("v1", "Service", "services"), true);
+    catalog.insert(api_resource("v1", "ConfigMap", "configmaps"), true);
+    catalog
+}
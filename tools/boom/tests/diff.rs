@@ -0,0 +1,79 @@
+use boom::diff::{strip_noise, to_yaml, unified_diff};
+use serde_json::json;
+
+#[test]
+fn strip_noise_removes_server_managed_fields() {
+    let mut obj = json!({
+        "apiVersion": "v1",
+        "kind": "ConfigMap",
+        "metadata": {
+            "name": "config",
+            "resourceVersion": "123",
+            "uid": "abc",
+            "generation": 2,
+            "creationTimestamp": "2026-01-01T00:00:00Z",
+            "managedFields": [{ "manager": "boom" }],
+            "annotations": {
+                "kubectl.kubernetes.io/last-applied-configuration": "{}"
+            }
+        },
+        "data": { "key": "value" },
+        "status": { "phase": "Active" }
+    });
+
+    strip_noise(&mut obj);
+
+    assert_eq!(
+        obj,
+        json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": { "name": "config" },
+            "data": { "key": "value" }
+        })
+    );
+}
+
+#[test]
+fn strip_noise_keeps_user_annotations() {
+    let mut obj = json!({
+        "metadata": {
+            "name": "web",
+            "annotations": {
+                "deployment.kubernetes.io/revision": "4",
+                "team": "platform"
+            }
+        }
+    });
+
+    strip_noise(&mut obj);
+
+    assert_eq!(
+        obj["metadata"]["annotations"],
+        json!({ "team": "platform" })
+    );
+}
+
+#[test]
+fn unified_diff_empty_when_unchanged() {
+    let yaml = to_yaml(&json!({ "data": { "key": "value" } }));
+    assert!(unified_diff(&yaml, &yaml, "ConfigMap/config").is_empty());
+}
+
+#[test]
+fn unified_diff_shows_changed_lines() {
+    let live = to_yaml(&json!({ "data": { "key": "old" } }));
+    let desired = to_yaml(&json!({ "data": { "key": "new" } }));
+    let diff = unified_diff(&live, &desired, "ConfigMap/config");
+    assert!(diff.contains("--- live/ConfigMap/config"), "{diff}");
+    assert!(diff.contains("+++ desired/ConfigMap/config"), "{diff}");
+    assert!(diff.contains("-  key: old"), "{diff}");
+    assert!(diff.contains("+  key: new"), "{diff}");
+}
+
+#[test]
+fn new_resource_diffs_against_empty() {
+    let desired = to_yaml(&json!({ "kind": "Service" }));
+    let diff = unified_diff(&to_yaml(&serde_json::Value::Null), &desired, "Service/web");
+    assert!(diff.contains("+kind: Service"), "{diff}");
+}
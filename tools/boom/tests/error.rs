@@ -0,0 +1,37 @@
+use boom::error::BoomError;
+
+#[test]
+fn exit_codes_are_distinct() {
+    let errors = [
+        BoomError::Usage(String::new()),
+        BoomError::Render(String::new()),
+        BoomError::Auth(String::new()),
+        BoomError::Apply(String::new()),
+        BoomError::Failed(String::new()),
+        BoomError::Timeout(String::new()),
+    ];
+    let mut codes: Vec<i32> = errors.iter().map(BoomError::exit_code).collect();
+    codes.sort_unstable();
+    codes.dedup();
+    assert_eq!(codes.len(), errors.len());
+}
+
+#[test]
+fn readiness_exit_codes_are_unchanged() {
+    assert_eq!(BoomError::Failed(String::new()).exit_code(), 1);
+    assert_eq!(BoomError::Timeout(String::new()).exit_code(), 70);
+}
+
+#[test]
+fn display_is_the_message() {
+    let err = BoomError::Render("cannot read directory k8s: not found".to_string());
+    assert_eq!(err.to_string(), "cannot read directory k8s: not found");
+    assert_eq!(err.kind(), "render");
+}
+
+#[test]
+fn with_context_keeps_kind() {
+    let err = BoomError::Timeout("not ready".to_string()).with_context("prod");
+    assert_eq!(err, BoomError::Timeout("prod: not ready".to_string()));
+    assert_eq!(err.exit_code(), 70);
+}
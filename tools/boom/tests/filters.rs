@@ -0,0 +1,112 @@
+use boom::render::{RenderOptions, environment};
+use serde_json::json;
+
+fn render(template: &str, ctx: &serde_json::Value) -> Result<String, String> {
+    environment(&RenderOptions::default())
+        .render_str(template, ctx)
+        .map_err(|e| e.to_string())
+}
+
+#[test]
+fn b64_round_trip() {
+    let ctx = json!({ "password": "hunter2" });
+    assert_eq!(
+        render("{{ password | b64encode }}", &ctx).unwrap(),
+        "aHVudGVyMg=="
+    );
+    assert_eq!(
+        render("{{ 'aHVudGVyMg==' | b64decode }}", &ctx).unwrap(),
+        "hunter2"
+    );
+}
+
+#[test]
+fn b64decode_rejects_invalid_input() {
+    assert!(render("{{ '***' | b64decode }}", &json!({})).is_err());
+}
+
+#[test]
+fn sha256_hex_digest() {
+    assert_eq!(
+        render("{{ 'abc' | sha256 }}", &json!({})).unwrap(),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+}
+
+#[test]
+fn to_yaml_and_to_json() {
+    let ctx = json!({ "cfg": { "port": 80 } });
+    assert_eq!(render("{{ cfg | to_yaml }}", &ctx).unwrap(), "port: 80");
+    assert_eq!(
+        render("{{ cfg | to_json }}", &ctx).unwrap(),
+        r#"{"port":80}"#
+    );
+}
+
+#[test]
+fn indent_is_builtin_and_nindent_uses_helm_semantics() {
+    let ctx = json!({ "body": "a: 1\nb: 2" });
+    assert_eq!(
+        render("{{ body | indent(2) }}", &ctx).unwrap(),
+        "a: 1\n  b: 2"
+    );
+    assert_eq!(
+        render("{{ body | indent(2, true) }}", &ctx).unwrap(),
+        "  a: 1\n  b: 2"
+    );
+    assert_eq!(
+        render("x:{{ body | nindent(2) }}", &ctx).unwrap(),
+        "x:\n  a: 1\n  b: 2"
+    );
+}
+
+#[test]
+fn quote_escapes_strings() {
+    let ctx = json!({ "v": "say \"hi\"", "n": 8080 });
+    assert_eq!(render("{{ v | quote }}", &ctx).unwrap(), r#""say \"hi\"""#);
+    assert_eq!(render("{{ n | quote }}", &ctx).unwrap(), r#""8080""#);
+}
+
+#[test]
+fn required_fails_with_message() {
+    let err = render("{{ image | required('image is required') }}", &json!({})).unwrap_err();
+    assert!(err.contains("image is required"), "{err}");
+    assert_eq!(
+        render("{{ image | required('x') }}", &json!({ "image": "app:1" })).unwrap(),
+        "app:1"
+    );
+}
+
+#[test]
+fn default_is_builtin_with_helm_semantics_behind_true() {
+    let ctx = json!({ "empty": "", "set": "v" });
+    assert_eq!(render("{{ missing | default('d') }}", &ctx).unwrap(), "d");
+    assert_eq!(render("{{ empty | default('d') }}", &ctx).unwrap(), "");
+    assert_eq!(
+        render("{{ empty | default('d', true) }}", &ctx).unwrap(),
+        "d"
+    );
+    assert_eq!(render("{{ set | default('d', true) }}", &ctx).unwrap(), "v");
+}
+
+#[test]
+fn env_is_disabled_by_default() {
+    let err = render("{{ env('HOME') }}", &json!({})).unwrap_err();
+    assert!(err.contains("--allow-env"), "{err}");
+}
+
+#[test]
+fn env_reads_variables_when_allowed() {
+    let env = environment(&RenderOptions { allow_env: true });
+    let path = std::env::var("PATH").unwrap_or_default();
+    assert_eq!(env.render_str("{{ env('PATH') }}", ()).unwrap(), path);
+    assert_eq!(
+        env.render_str("{{ env('BOOM_SURELY_UNSET_VAR', 'fallback') }}", ())
+            .unwrap(),
+        "fallback"
+    );
+    assert!(
+        env.render_str("{{ env('BOOM_SURELY_UNSET_VAR') }}", ())
+            .is_err()
+    );
+}
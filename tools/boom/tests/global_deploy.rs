@@ -1,38 +1,24 @@
-use boom::manifest;
+mod common;
 
-#[test]
-fn is_cluster_scoped_returns_true_for_cluster_scoped_kinds() {
-    let cluster_scoped = [
-        "ClusterRole",
-        "ClusterRoleBinding",
-        "Namespace",
-        "CustomResourceDefinition",
-        "PersistentVolume",
-        "StorageClass",
-        "IngressClass",
-        "PriorityClass",
-    ];
-    for kind in cluster_scoped {
-        assert!(
-            manifest::is_cluster_scoped(kind),
-            "{kind} should be cluster-scoped"
-        );
-    }
-}
+use boom::global_deploy::namespaced_resources;
+use boom::manifest;
+use common::catalog;
 
 #[test]
-fn is_cluster_scoped_returns_false_for_namespaced_kinds() {
-    let namespaced = ["Deployment", "Service", "ConfigMap"];
-    for kind in namespaced {
-        assert!(
-            !manifest::is_cluster_scoped(kind),
-            "{kind} should not be cluster-scoped"
-        );
-    }
+fn catalog_reports_scope_for_known_kinds() {
+    let catalog = catalog();
+    assert_eq!(catalog.is_namespaced("v1", "Namespace"), Some(false));
+    assert_eq!(
+        catalog.is_namespaced("rbac.authorization.k8s.io/v1", "ClusterRole"),
+        Some(false)
+    );
+    assert_eq!(catalog.is_namespaced("apps/v1", "Deployment"), Some(true));
+    assert_eq!(catalog.is_namespaced("v1", "Widget"), None);
 }
 
 #[test]
-fn filter_mixed_manifests_yields_only_cluster_scoped() {
+#[allow(clippy::needless_raw_string_hashes)]
+fn filter_mixed_manifests_yields_only_namespaced() {
     let yaml = r#"
 apiVersion: v1
 kind: Namespace
@@ -60,13 +46,54 @@ metadata:
   name: my-cm
 "#;
 
-    let resources = manifest::parse_manifests(yaml);
-    let cluster_only: Vec<_> = resources
-        .iter()
-        .filter(|r| manifest::is_cluster_scoped(&r.kind))
-        .collect();
+    let resources = manifest::parse_manifests(yaml).unwrap();
+    let rejected = namespaced_resources(&catalog(), &resources);
+
+    assert_eq!(
+        rejected,
+        vec!["Deployment/my-deploy", "Service/my-svc", "ConfigMap/my-cm"]
+    );
+}
+
+#[test]
+fn unknown_kinds_are_rejected() {
+    let yaml = r"
+apiVersion: example.com/v1
+kind: Widget
+metadata:
+  name: thing
+";
+    let resources = manifest::parse_manifests(yaml).unwrap();
+    assert_eq!(
+        namespaced_resources(&catalog(), &resources),
+        vec!["Widget/thing"]
+    );
+}
+
+#[test]
+fn register_crds_uses_manifest_plural_and_scope() {
+    let yaml = r"
+apiVersion: apiextensions.k8s.io/v1
+kind: CustomResourceDefinition
+metadata:
+  name: widgets.example.com
+spec:
+  group: example.com
+  scope: Cluster
+  names:
+    kind: Widget
+    plural: widgetries
+  versions:
+    - name: v1alpha1
+    - name: v1
+";
+    let resources = manifest::parse_manifests(yaml).unwrap();
+    let mut catalog = catalog();
+    catalog.register_crds(&resources);
 
-    assert_eq!(cluster_only.len(), 2);
-    assert_eq!(cluster_only[0].kind, "Namespace");
-    assert_eq!(cluster_only[1].kind, "ClusterRole");
+    for version in ["example.com/v1alpha1", "example.com/v1"] {
+        let ty = catalog.resolve(version, "Widget").expect("registered");
+        assert_eq!(ty.resource.plural, "widgetries");
+        assert!(!ty.namespaced);
+    }
 }
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use boom::hints::{RequiredRollout, ResourceHints, parse_duration, resource_hints};
+use boom::manifest::parse_manifests;
+
+fn with_annotations(annotations: &str) -> boom::manifest::ResourceDescriptor {
+    let yaml = format!(
+        "apiVersion: apps/v1\nkind: StatefulSet\nmetadata:\n  name: db\n  annotations:\n{annotations}"
+    );
+    parse_manifests(&yaml).unwrap().remove(0)
+}
+
+#[test]
+fn parse_duration_accepts_units() {
+    assert_eq!(parse_duration("900"), Ok(Duration::from_mins(15)));
+    assert_eq!(parse_duration("900s"), Ok(Duration::from_mins(15)));
+    assert_eq!(parse_duration("15m"), Ok(Duration::from_mins(15)));
+    assert_eq!(parse_duration("2h"), Ok(Duration::from_hours(2)));
+}
+
+#[test]
+fn parse_duration_rejects_garbage() {
+    assert!(parse_duration("").is_err());
+    assert!(parse_duration("soon").is_err());
+    assert!(parse_duration("10d").is_err());
+}
+
+#[test]
+fn parse_duration_rejects_overflow() {
+    let hours = format!("{}h", u64::MAX / 3600 + 1);
+    assert_eq!(
+        parse_duration(&hours),
+        Err(format!("invalid duration {hours:?}"))
+    );
+    assert!(parse_duration(&format!("{}m", u64::MAX)).is_err());
+}
+
+#[test]
+fn required_rollout_values() {
+    assert_eq!(RequiredRollout::parse("full"), Ok(RequiredRollout::Full));
+    assert_eq!(RequiredRollout::parse("none"), Ok(RequiredRollout::None));
+    assert_eq!(
+        RequiredRollout::parse("maxUnavailable"),
+        Ok(RequiredRollout::MaxUnavailable)
+    );
+    assert_eq!(
+        RequiredRollout::parse("90%"),
+        Ok(RequiredRollout::Percent(90))
+    );
+    assert!(RequiredRollout::parse("150%").is_err());
+    assert!(RequiredRollout::parse("most").is_err());
+}
+
+#[test]
+fn resource_hints_read_annotations() {
+    let resource = with_annotations(
+        "    boom.io/timeout: 15m\n    boom.io/skip-verify: \"true\"\n    boom.io/required-rollout: maxUnavailable\n",
+    );
+    assert_eq!(
+        resource_hints(&resource),
+        Ok(ResourceHints {
+            timeout: Some(Duration::from_mins(15)),
+            skip_verify: true,
+        })
+    );
+}
+
+#[test]
+fn resource_hints_default_without_annotations() {
+    let resource = parse_manifests("apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: app\n")
+        .unwrap()
+        .remove(0);
+    assert_eq!(resource_hints(&resource), Ok(ResourceHints::default()));
+}
+
+#[test]
+fn resource_hints_report_invalid_values() {
+    let resource = with_annotations("    boom.io/timeout: forever\n");
+    let err = resource_hints(&resource).unwrap_err();
+    assert!(err.contains("StatefulSet/db"), "{err}");
+    assert!(err.contains("boom.io/timeout"), "{err}");
+}
+
+#[test]
+fn resource_hints_validate_required_rollout() {
+    let resource = with_annotations("    boom.io/required-rollout: most\n");
+    let err = resource_hints(&resource).unwrap_err();
+    assert!(err.contains("boom.io/required-rollout"), "{err}");
+}
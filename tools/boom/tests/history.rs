@@ -0,0 +1,132 @@
+use boom::history::{
+    HISTORY_LABEL, REVISION_LABEL, Revision, STATUS_LABEL, only_owner, revision_name,
+    revision_secret, rollback_target, serialize_manifests,
+};
+use boom::manifest::{OWNER_LABEL, parse_manifests, parse_source};
+
+#[test]
+fn revision_name_is_a_valid_object_name() {
+    assert_eq!(revision_name("My_App", 3), "boom-history-my-app-v3");
+}
+
+fn succeeded(numbers: &[u32]) -> Vec<Revision> {
+    numbers
+        .iter()
+        .map(|&number| Revision {
+            number,
+            succeeded: true,
+        })
+        .collect()
+}
+
+#[test]
+fn rollback_target_defaults_to_previous_revision() {
+    assert_eq!(rollback_target(&succeeded(&[3, 1, 2]), None), Ok(2));
+}
+
+#[test]
+fn rollback_target_after_failed_deploy_restores_last_good_revision() {
+    let mut revisions = succeeded(&[1, 2]);
+    revisions.push(Revision {
+        number: 3,
+        succeeded: false,
+    });
+    assert_eq!(rollback_target(&revisions, None), Ok(2));
+}
+
+#[test]
+fn rollback_target_skips_failed_revisions() {
+    let revisions = [
+        Revision {
+            number: 1,
+            succeeded: true,
+        },
+        Revision {
+            number: 2,
+            succeeded: false,
+        },
+        Revision {
+            number: 3,
+            succeeded: true,
+        },
+    ];
+    assert_eq!(rollback_target(&revisions, None), Ok(1));
+}
+
+#[test]
+fn rollback_target_requires_history() {
+    assert!(rollback_target(&[], None).is_err());
+    assert!(rollback_target(&succeeded(&[1]), None).is_err());
+}
+
+#[test]
+fn rollback_target_accepts_known_revision() {
+    assert_eq!(rollback_target(&succeeded(&[1, 2, 3]), Some(1)), Ok(1));
+    assert!(rollback_target(&succeeded(&[1, 2, 3]), Some(7)).is_err());
+}
+
+#[test]
+fn serialized_manifests_round_trip() {
+    let yaml = r"
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: config
+  labels:
+    boom.io/owner: web
+data:
+  key: value
+---
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+";
+    let resources = parse_manifests(yaml).unwrap();
+    let serialized = serialize_manifests(&resources).unwrap();
+    let restored = parse_source("boom-history-web-v1", &serialized).unwrap();
+
+    assert_eq!(restored.len(), 2);
+    assert_eq!(restored[0].raw, resources[0].raw);
+    assert_eq!(restored[1].raw, resources[1].raw);
+    assert_eq!(
+        restored[0].raw["metadata"]["labels"][OWNER_LABEL].as_str(),
+        Some("web")
+    );
+}
+
+#[test]
+fn revision_secret_is_labeled_for_history_lookup() {
+    let revision = Revision {
+        number: 4,
+        succeeded: false,
+    };
+    let secret = revision_secret("web", revision, "kind: ConfigMap\n");
+    let labels = secret.metadata.labels.unwrap();
+    assert_eq!(labels[HISTORY_LABEL], "web");
+    assert_eq!(labels[REVISION_LABEL], "4");
+    assert_eq!(labels[STATUS_LABEL], "failed");
+    assert!(
+        !labels.contains_key(OWNER_LABEL),
+        "history must not carry the ownership label or prune would delete it"
+    );
+    assert_eq!(secret.metadata.name.as_deref(), Some("boom-history-web-v4"));
+    assert_eq!(
+        secret.string_data.unwrap()["manifests"],
+        "kind: ConfigMap\n"
+    );
+}
+
+#[test]
+fn only_owner_picks_the_single_recorded_deploy() {
+    let owners = ["web-1a2b3c4d".to_string()].into();
+    assert_eq!(only_owner(&owners).as_deref(), Ok("web-1a2b3c4d"));
+}
+
+#[test]
+fn only_owner_requires_exactly_one() {
+    assert!(only_owner(&std::collections::BTreeSet::new()).is_err());
+    let owners = ["api".to_string(), "web".to_string()].into();
+    let err = only_owner(&owners).unwrap_err();
+    assert!(err.ends_with("pass --owner: api, web"), "{err}");
+}
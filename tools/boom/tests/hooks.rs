@@ -0,0 +1,102 @@
+mod common;
+
+use boom::hooks::{DeletePolicy, HookKind, delete_policies, hook_kind, split_hooks};
+use boom::manifest::ResourceDescriptor;
+use common::resource;
+
+#[test]
+fn hook_kind_absent_without_annotation() {
+    assert_eq!(hook_kind(&resource("Job", "migrate", &[])), Ok(None));
+}
+
+#[test]
+fn hook_kind_parses_pre_and_post() {
+    let pre = resource("Job", "migrate", &[("boom.io/hook", "pre-deploy")]);
+    let post = resource("Pod", "smoke", &[("boom.io/hook", "post-deploy")]);
+    assert_eq!(hook_kind(&pre), Ok(Some(HookKind::PreDeploy)));
+    assert_eq!(hook_kind(&post), Ok(Some(HookKind::PostDeploy)));
+}
+
+#[test]
+fn hook_kind_rejects_unknown_value() {
+    let job = resource("Job", "migrate", &[("boom.io/hook", "pre-install")]);
+    assert!(hook_kind(&job).unwrap_err().contains("pre-install"));
+}
+
+#[test]
+fn hook_kind_rejects_non_job_kinds() {
+    let deploy = resource("Deployment", "web", &[("boom.io/hook", "pre-deploy")]);
+    assert!(
+        hook_kind(&deploy)
+            .unwrap_err()
+            .contains("only Jobs and Pods")
+    );
+}
+
+#[test]
+fn delete_policy_defaults_to_before_hook_creation() {
+    assert_eq!(
+        delete_policies(&resource("Job", "migrate", &[])),
+        Ok(vec![DeletePolicy::BeforeHookCreation])
+    );
+}
+
+#[test]
+fn delete_policy_parses_list() {
+    let job = resource(
+        "Job",
+        "migrate",
+        &[(
+            "boom.io/hook-delete-policy",
+            "before-hook-creation, hook-succeeded",
+        )],
+    );
+    assert_eq!(
+        delete_policies(&job),
+        Ok(vec![
+            DeletePolicy::BeforeHookCreation,
+            DeletePolicy::HookSucceeded
+        ])
+    );
+}
+
+#[test]
+fn delete_policy_rejects_unknown_value() {
+    let job = resource(
+        "Job",
+        "migrate",
+        &[("boom.io/hook-delete-policy", "always")],
+    );
+    assert!(delete_policies(&job).unwrap_err().contains("always"));
+}
+
+#[test]
+fn split_hooks_separates_in_order() {
+    let split = split_hooks(vec![
+        resource("Job", "migrate", &[("boom.io/hook", "pre-deploy")]),
+        resource("Deployment", "web", &[]),
+        resource("Job", "smoke", &[("boom.io/hook", "post-deploy")]),
+        resource("Job", "seed", &[("boom.io/hook", "pre-deploy")]),
+        resource("Service", "web", &[]),
+    ])
+    .unwrap();
+
+    let names =
+        |list: &[ResourceDescriptor]| list.iter().map(|r| r.name.clone()).collect::<Vec<_>>();
+    assert_eq!(names(&split.pre_deploy), ["migrate", "seed"]);
+    assert_eq!(names(&split.resources), ["web", "web"]);
+    assert_eq!(names(&split.post_deploy), ["smoke"]);
+}
+
+#[test]
+fn split_hooks_propagates_invalid_policy() {
+    let result = split_hooks(vec![resource(
+        "Job",
+        "migrate",
+        &[
+            ("boom.io/hook", "pre-deploy"),
+            ("boom.io/hook-delete-policy", "never"),
+        ],
+    )]);
+    assert!(result.is_err());
+}
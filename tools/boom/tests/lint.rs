@@ -0,0 +1,182 @@
+use boom::lint::{Level, LintConfig, is_latest, lint};
+use boom::manifest::parse_source;
+
+const CLEAN: &str = "\
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+spec:
+  selector:
+    matchLabels:
+      app: web
+  template:
+    metadata:
+      labels:
+        app: web
+    spec:
+      containers:
+        - name: web
+          image: nginx:1.27
+          readinessProbe:
+            httpGet:
+              path: /
+              port: 80
+          resources:
+            requests:
+              cpu: 100m
+            limits:
+              memory: 128Mi
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: web
+spec:
+  selector:
+    app: web
+  ports:
+    - port: 80
+";
+
+fn findings(yaml: &str, config: &LintConfig) -> Vec<(String, Level, String)> {
+    let resources = parse_source("app.yml", yaml).unwrap();
+    lint(&resources, config)
+        .into_iter()
+        .map(|f| (f.rule.to_string(), f.level, f.message))
+        .collect()
+}
+
+fn rules(yaml: &str) -> Vec<String> {
+    findings(yaml, &LintConfig::default())
+        .into_iter()
+        .map(|(rule, _, _)| rule)
+        .collect()
+}
+
+#[test]
+fn clean_manifests_have_no_findings() {
+    assert!(rules(CLEAN).is_empty());
+}
+
+#[test]
+fn latest_tag_detection() {
+    assert!(is_latest("nginx"));
+    assert!(is_latest("nginx:latest"));
+    assert!(is_latest("registry:5000/team/app"));
+    assert!(!is_latest("registry:5000/team/app:1.2"));
+    assert!(!is_latest("nginx@sha256:abc"));
+}
+
+#[test]
+fn reports_each_rule() {
+    let yaml = "\
+apiVersion: apps/v1
+kind: DaemonSet
+metadata:
+  name: agent
+spec:
+  template:
+    metadata:
+      labels:
+        app: agent
+    spec:
+      containers:
+        - name: agent
+          image: agent:latest
+          securityContext:
+            privileged: true
+      volumes:
+        - name: root
+          hostPath:
+            path: /
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: api
+spec:
+  selector:
+    app: api
+";
+    let findings = findings(yaml, &LintConfig::default());
+    let messages: Vec<&str> = findings.iter().map(|(_, _, m)| m.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "container agent has no resources.requests",
+            "container agent has no resources.limits",
+            "container agent uses unpinned image agent:latest",
+            "container agent has no readinessProbe",
+            "container agent runs privileged",
+            "volume root mounts hostPath /",
+            "selector app=api matches no workload in these manifests",
+        ]
+    );
+}
+
+#[test]
+fn init_containers_are_checked() {
+    let yaml = "\
+apiVersion: batch/v1
+kind: Job
+metadata:
+  name: migrate
+spec:
+  template:
+    spec:
+      initContainers:
+        - name: wait
+          image: busybox
+          resources:
+            requests: {cpu: 10m}
+            limits: {cpu: 10m}
+      containers:
+        - name: migrate
+          image: app:1.0
+          resources:
+            requests: {cpu: 10m}
+            limits: {cpu: 10m}
+";
+    // Jobs run to completion, so no readiness probe is expected.
+    assert_eq!(rules(yaml), ["latest-tag"]);
+}
+
+#[test]
+fn duplicate_resources() {
+    let yaml = format!("{CLEAN}---\n{}", CLEAN.split("---\n").nth(1).unwrap());
+    assert_eq!(rules(&yaml), ["duplicate-resource"]);
+}
+
+#[test]
+fn config_disables_and_overrides_levels() {
+    let yaml = CLEAN
+        .replace("nginx:1.27", "nginx")
+        .replace("app: web\n  ports", "app: other\n  ports");
+    let config =
+        LintConfig::parse("rules:\n  latest-tag: warning\n  service-selector: off\n").unwrap();
+    let findings = findings(&yaml, &config);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].0, "latest-tag");
+    assert_eq!(findings[0].1, Level::Warning);
+}
+
+#[test]
+fn config_rejects_unknown_rules() {
+    let err = LintConfig::parse("rules:\n  no-such-rule: off\n").unwrap_err();
+    assert!(err.contains("no-such-rule"));
+    assert!(LintConfig::parse("rule:\n  latest-tag: off\n").is_err());
+}
+
+#[test]
+fn annotation_disables_rules_per_resource() {
+    let yaml = CLEAN.replacen(
+        "  name: web\n",
+        "  name: web\n  annotations:\n    boom.io/lint-disable: \"latest-tag, missing-readiness-probe\"\n",
+        1,
+    );
+    let yaml = yaml
+        .replace("nginx:1.27", "nginx")
+        .replace("          readinessProbe:\n            httpGet:\n              path: /\n              port: 80\n", "");
+    assert!(rules(&yaml).is_empty());
+}
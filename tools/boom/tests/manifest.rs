@@ -1,6 +1,11 @@
-use boom::manifest::{ResourceDescriptor, parse_manifests, priority_tier, sort_by_priority};
+use boom::manifest::{
+    OWNER_LABEL, ResourceDescriptor, owner_from_dir, parse_files, parse_manifests, parse_source,
+    priority_tier, sanitize_label_value, set_owner_label, sort_by_priority,
+};
+use boom::render::RenderedFile;
 
 #[test]
+#[allow(clippy::needless_raw_string_hashes)]
 fn parse_single_doc() {
     let yaml = r#"
 apiVersion: apps/v1
@@ -9,7 +14,7 @@ metadata:
   name: web
   namespace: production
 "#;
-    let resources = parse_manifests(yaml);
+    let resources = parse_manifests(yaml).unwrap();
     assert_eq!(resources.len(), 1);
     assert_eq!(resources[0].api_version, "apps/v1");
     assert_eq!(resources[0].kind, "Deployment");
@@ -18,6 +23,7 @@ metadata:
 }
 
 #[test]
+#[allow(clippy::needless_raw_string_hashes)]
 fn parse_multi_doc() {
     let yaml = r#"apiVersion: v1
 kind: Namespace
@@ -36,7 +42,7 @@ kind: Secret
 metadata:
   name: creds
 "#;
-    let resources = parse_manifests(yaml);
+    let resources = parse_manifests(yaml).unwrap();
     assert_eq!(
         resources.len(),
         3,
@@ -44,6 +50,130 @@ metadata:
     );
 }
 
+#[test]
+fn parse_leading_separator_and_comments() {
+    let yaml = r"--- # first
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: config
+--- # second
+# only a comment
+---
+apiVersion: v1
+kind: Secret
+metadata:
+  name: creds
+";
+    let resources = parse_manifests(yaml).unwrap();
+    let names: Vec<_> = resources.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["config", "creds"]);
+}
+
+#[test]
+fn parse_separator_inside_block_scalar_is_not_split() {
+    let yaml = r"apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: config
+data:
+  script: |
+    echo start
+    ---not a separator
+";
+    let resources = parse_manifests(yaml).unwrap();
+    assert_eq!(resources.len(), 1);
+}
+
+#[test]
+fn parse_expands_list_kind() {
+    let yaml = r"
+apiVersion: v1
+kind: List
+items:
+  - apiVersion: v1
+    kind: ConfigMap
+    metadata:
+      name: a
+  - apiVersion: v1
+    kind: ConfigMap
+    metadata:
+      name: b
+";
+    let resources = parse_manifests(yaml).unwrap();
+    let names: Vec<_> = resources.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["a", "b"]);
+}
+
+#[test]
+fn parse_keeps_custom_kinds_ending_in_list() {
+    let yaml = r"
+apiVersion: net.example.com/v1
+kind: AllowList
+metadata:
+  name: office
+items:
+  - apiVersion: v1
+    kind: ConfigMap
+    metadata:
+      name: a
+";
+    let resources = parse_manifests(yaml).unwrap();
+    assert_eq!(resources.len(), 1);
+    assert_eq!(resources[0].kind, "AllowList");
+    assert_eq!(resources[0].name, "office");
+}
+
+#[test]
+fn parse_error_reports_source_and_document_index() {
+    let yaml = r"apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: ok
+---
+apiVersion: v1
+kind: ConfigMap
+metadata: [unclosed
+";
+    let err = parse_source("config.yml.j2", yaml).unwrap_err();
+    assert!(
+        err.starts_with("config.yml.j2: document 2: invalid YAML"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn parse_missing_name_is_error() {
+    let yaml = r"
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  labels: {}
+";
+    let err = parse_manifests(yaml).unwrap_err();
+    assert!(
+        err.contains("missing metadata.name"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn parse_files_records_source() {
+    let files = [
+        RenderedFile {
+            name: "a.yml".to_string(),
+            content: "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: a\n".to_string(),
+        },
+        RenderedFile {
+            name: "b.yml.j2".to_string(),
+            content: "apiVersion: v1\nkind: Secret\nmetadata:\n  name: b".to_string(),
+        },
+    ];
+    let resources = parse_files(&files).unwrap();
+    assert_eq!(resources[0].source, "a.yml");
+    assert_eq!(resources[1].source, "b.yml.j2");
+}
+
 #[test]
 fn tier_classification() {
     assert_eq!(priority_tier("Namespace"), 0);
@@ -61,6 +191,7 @@ fn sort_priority() {
             kind: "Deployment".to_string(),
             name: "web".to_string(),
             namespace: None,
+            source: String::new(),
             raw: serde_yaml::Value::Null,
         },
         ResourceDescriptor {
@@ -68,6 +199,7 @@ fn sort_priority() {
             kind: "ConfigMap".to_string(),
             name: "config".to_string(),
             namespace: None,
+            source: String::new(),
             raw: serde_yaml::Value::Null,
         },
         ResourceDescriptor {
@@ -75,6 +207,7 @@ fn sort_priority() {
             kind: "Namespace".to_string(),
             name: "ns".to_string(),
             namespace: None,
+            source: String::new(),
             raw: serde_yaml::Value::Null,
         },
         ResourceDescriptor {
@@ -82,6 +215,7 @@ fn sort_priority() {
             kind: "Deployment".to_string(),
             name: "api".to_string(),
             namespace: None,
+            source: String::new(),
             raw: serde_yaml::Value::Null,
         },
     ];
@@ -100,3 +234,71 @@ fn sort_priority() {
         "second Deployment keeps its position"
     );
 }
+
+#[test]
+fn set_owner_label_adds_label() {
+    let yaml = r"
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: config
+---
+apiVersion: v1
+kind: Secret
+metadata:
+  name: creds
+  labels:
+    app: web
+";
+    let mut resources = parse_manifests(yaml).unwrap();
+    set_owner_label(&mut resources, "my-app");
+
+    for resource in &resources {
+        let owner = resource.raw["metadata"]["labels"][OWNER_LABEL].as_str();
+        assert_eq!(owner, Some("my-app"), "{} should be labeled", resource.name);
+    }
+    assert_eq!(
+        resources[1].raw["metadata"]["labels"]["app"].as_str(),
+        Some("web"),
+        "existing labels should be preserved"
+    );
+}
+
+#[test]
+fn sanitize_label_value_replaces_invalid_chars() {
+    assert_eq!(sanitize_label_value("my app/prod"), "my-app-prod");
+    assert_eq!(sanitize_label_value("_deploy_"), "deploy");
+    assert_eq!(sanitize_label_value(&"a".repeat(80)).len(), 63);
+}
+
+#[test]
+fn owner_from_dir_distinguishes_same_basename() {
+    let repo = tempfile::tempdir().unwrap();
+    std::fs::create_dir(repo.path().join(".git")).unwrap();
+    for dir in ["foo/app", "bar/app"] {
+        std::fs::create_dir_all(repo.path().join(dir)).unwrap();
+    }
+    let foo = owner_from_dir(&repo.path().join("foo/app")).unwrap();
+    let bar = owner_from_dir(&repo.path().join("bar/app")).unwrap();
+    assert!(foo.starts_with("app-"), "{foo}");
+    assert!(bar.starts_with("app-"), "{bar}");
+    assert_ne!(foo, bar);
+}
+
+#[test]
+fn owner_from_dir_is_stable_across_checkouts() {
+    let owners: Vec<String> = (0..2)
+        .map(|_| {
+            let repo = tempfile::tempdir().unwrap();
+            std::fs::create_dir(repo.path().join(".git")).unwrap();
+            std::fs::create_dir_all(repo.path().join("deploy/app")).unwrap();
+            owner_from_dir(&repo.path().join("deploy/app")).unwrap()
+        })
+        .collect();
+    assert_eq!(owners[0], owners[1]);
+}
+
+#[test]
+fn owner_from_dir_missing_directory_errors() {
+    assert!(owner_from_dir(std::path::Path::new("/nonexistent/app")).is_err());
+}
@@ -1,4 +1,9 @@
-use boom::monitor::{ResourceState, is_ready};
+use boom::manifest::{OWNER_LABEL, ResourceDescriptor, parse_manifests};
+use boom::monitor::{
+    READY_CONDITION_ANNOTATION, ResourceState, current_pod_hash, describe_container,
+    failure_reason, is_ready, pod_failure, pod_selector, watch_groups, watcher_config,
+};
+use k8s_openapi::api::core::v1::ContainerStatus;
 use serde_json::json;
 
 #[test]
@@ -128,14 +133,446 @@ fn always_ready_secret() {
 }
 
 #[test]
-fn always_ready_pvc() {
+fn pvc_ready_when_bound() {
     assert_eq!(
-        is_ready("PersistentVolumeClaim", &json!({})),
+        is_ready(
+            "PersistentVolumeClaim",
+            &json!({"status": {"phase": "Pending"}})
+        ),
+        ResourceState::NotReady
+    );
+    assert_eq!(
+        is_ready(
+            "PersistentVolumeClaim",
+            &json!({"status": {"phase": "Bound"}})
+        ),
         ResourceState::Ready
     );
+    assert_eq!(
+        is_ready(
+            "PersistentVolumeClaim",
+            &json!({"status": {"phase": "Lost"}})
+        ),
+        ResourceState::Failed
+    );
 }
 
 #[test]
 fn always_ready_service() {
     assert_eq!(is_ready("Service", &json!({})), ResourceState::Ready);
 }
+
+#[test]
+fn load_balancer_service_waits_for_ingress() {
+    let pending = json!({ "spec": { "type": "LoadBalancer" }, "status": { "loadBalancer": {} } });
+    assert_eq!(is_ready("Service", &pending), ResourceState::NotReady);
+
+    let assigned = json!({
+        "spec": { "type": "LoadBalancer" },
+        "status": { "loadBalancer": { "ingress": [{ "ip": "10.0.0.1" }] } }
+    });
+    assert_eq!(is_ready("Service", &assigned), ResourceState::Ready);
+}
+
+#[test]
+fn ingress_ready_when_load_balancer_assigned() {
+    assert_eq!(is_ready("Ingress", &json!({})), ResourceState::NotReady);
+    let assigned =
+        json!({ "status": { "loadBalancer": { "ingress": [{ "hostname": "lb.example.com" }] } } });
+    assert_eq!(is_ready("Ingress", &assigned), ResourceState::Ready);
+}
+
+#[test]
+fn hpa_ready_when_able_to_scale() {
+    let status =
+        json!({ "status": { "conditions": [{ "type": "AbleToScale", "status": "True" }] } });
+    assert_eq!(
+        is_ready("HorizontalPodAutoscaler", &status),
+        ResourceState::Ready
+    );
+    assert_eq!(
+        is_ready("HorizontalPodAutoscaler", &json!({})),
+        ResourceState::NotReady
+    );
+}
+
+#[test]
+fn crd_ready_when_established() {
+    let established = json!({ "status": { "conditions": [
+        { "type": "NamesAccepted", "status": "True" },
+        { "type": "Established", "status": "True" }
+    ] } });
+    assert_eq!(
+        is_ready("CustomResourceDefinition", &established),
+        ResourceState::Ready
+    );
+
+    let conflict =
+        json!({ "status": { "conditions": [{ "type": "NamesAccepted", "status": "False" }] } });
+    assert_eq!(
+        is_ready("CustomResourceDefinition", &conflict),
+        ResourceState::Failed
+    );
+}
+
+#[test]
+fn namespace_ready_when_active() {
+    let active = json!({ "status": { "phase": "Active" } });
+    assert_eq!(is_ready("Namespace", &active), ResourceState::Ready);
+    let terminating = json!({ "status": { "phase": "Terminating" } });
+    assert_eq!(is_ready("Namespace", &terminating), ResourceState::NotReady);
+}
+
+#[test]
+fn passive_kinds_ready_once_applied() {
+    for kind in [
+        "ServiceAccount",
+        "Role",
+        "NetworkPolicy",
+        "PodDisruptionBudget",
+        "CronJob",
+    ] {
+        assert_eq!(is_ready(kind, &json!({})), ResourceState::Ready, "{kind}");
+    }
+}
+
+fn descriptor(kind: &str, name: &str, namespace: &str, owner: Option<&str>) -> ResourceDescriptor {
+    let labels = owner.map_or_else(String::new, |o| {
+        format!("\n  labels:\n    {OWNER_LABEL}: {o}")
+    });
+    let yaml = format!(
+        "apiVersion: apps/v1\nkind: {kind}\nmetadata:\n  name: {name}\n  namespace: {namespace}{labels}\n"
+    );
+    parse_manifests(&yaml).unwrap().remove(0)
+}
+
+#[test]
+fn watch_groups_split_by_kind_and_namespace() {
+    let resources = vec![
+        descriptor("Deployment", "web", "prod", None),
+        descriptor("Deployment", "api", "prod", None),
+        descriptor("Deployment", "web", "staging", None),
+        descriptor("StatefulSet", "db", "prod", None),
+    ];
+    let groups = watch_groups(&resources);
+    assert_eq!(groups.len(), 3);
+
+    let prod_deployments = groups
+        .iter()
+        .find(|(g, _)| g.kind == "Deployment" && g.namespace == "prod")
+        .unwrap()
+        .1;
+    let names: Vec<&str> = prod_deployments.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["web", "api"]);
+}
+
+#[test]
+fn watcher_config_selects_single_object_by_name() {
+    let resources = [descriptor("Deployment", "web", "prod", Some("app"))];
+    let members: Vec<&ResourceDescriptor> = resources.iter().collect();
+    let config = watcher_config(&members);
+    assert_eq!(config.field_selector.as_deref(), Some("metadata.name=web"));
+    assert_eq!(config.label_selector, None);
+}
+
+#[test]
+fn watcher_config_uses_shared_owner_label() {
+    let resources = [
+        descriptor("Deployment", "web", "prod", Some("app")),
+        descriptor("Deployment", "api", "prod", Some("app")),
+    ];
+    let members: Vec<&ResourceDescriptor> = resources.iter().collect();
+    let config = watcher_config(&members);
+    assert_eq!(config.label_selector, Some(format!("{OWNER_LABEL}=app")));
+    assert_eq!(config.field_selector, None);
+}
+
+#[test]
+fn watcher_config_without_shared_owner_watches_namespace() {
+    let resources = [
+        descriptor("Deployment", "web", "prod", Some("app")),
+        descriptor("Deployment", "api", "prod", None),
+    ];
+    let members: Vec<&ResourceDescriptor> = resources.iter().collect();
+    let config = watcher_config(&members);
+    assert_eq!(config.label_selector, None);
+    assert_eq!(config.field_selector, None);
+}
+
+#[test]
+fn custom_resource_ready_when_ready_condition_true() {
+    let status = json!({
+        "metadata": { "generation": 2 },
+        "status": {
+            "observedGeneration": 2,
+            "conditions": [{ "type": "Ready", "status": "True" }]
+        }
+    });
+    assert_eq!(is_ready("Certificate", &status), ResourceState::Ready);
+}
+
+#[test]
+fn custom_resource_falls_back_to_available_condition() {
+    let status = json!({ "status": { "conditions": [{ "type": "Available", "status": "True" }] } });
+    assert_eq!(is_ready("Database", &status), ResourceState::Ready);
+    assert_eq!(is_ready("Database", &json!({})), ResourceState::NotReady);
+}
+
+#[test]
+fn custom_resource_not_ready_while_generation_unobserved() {
+    let status = json!({
+        "metadata": { "generation": 3 },
+        "status": {
+            "observedGeneration": 2,
+            "conditions": [{ "type": "Ready", "status": "True" }]
+        }
+    });
+    assert_eq!(is_ready("Certificate", &status), ResourceState::NotReady);
+}
+
+#[test]
+fn custom_resource_failed_on_terminal_reason() {
+    let failed = json!({ "status": { "conditions": [
+        { "type": "Ready", "status": "False", "reason": "IssuanceFailed" }
+    ] } });
+    assert_eq!(is_ready("Certificate", &failed), ResourceState::Failed);
+
+    let pending = json!({ "status": { "conditions": [
+        { "type": "Ready", "status": "False", "reason": "Pending" }
+    ] } });
+    assert_eq!(is_ready("Certificate", &pending), ResourceState::NotReady);
+}
+
+#[test]
+fn ready_condition_annotation_overrides_default() {
+    let status = json!({
+        "metadata": { "annotations": { READY_CONDITION_ANNOTATION: "Synced" } },
+        "status": { "conditions": [
+            { "type": "Ready", "status": "False", "reason": "Waiting" },
+            { "type": "Synced", "status": "True" }
+        ] }
+    });
+    assert_eq!(is_ready("Database", &status), ResourceState::Ready);
+}
+
+#[test]
+fn deployment_failed_when_progress_deadline_exceeded() {
+    let status = json!({
+        "spec": { "replicas": 3 },
+        "status": {
+            "availableReplicas": 1,
+            "updatedReplicas": 1,
+            "conditions": [{
+                "type": "Progressing",
+                "status": "False",
+                "reason": "ProgressDeadlineExceeded",
+                "message": "ReplicaSet \"web-5d9\" has timed out progressing."
+            }]
+        }
+    });
+    assert_eq!(is_ready("Deployment", &status), ResourceState::Failed);
+    assert_eq!(
+        failure_reason("Deployment", &status).as_deref(),
+        Some("ProgressDeadlineExceeded: ReplicaSet \"web-5d9\" has timed out progressing.")
+    );
+}
+
+#[test]
+fn pod_failure_reports_container_reason() {
+    for reason in ["ErrImagePull", "CreateContainerConfigError"] {
+        let pod = json!({ "status": { "containerStatuses": [
+            { "name": "app", "state": { "waiting": { "reason": reason } } }
+        ] } });
+        assert_eq!(pod_failure(&pod), Some(format!("container app: {reason}")));
+        assert_eq!(is_ready("Pod", &pod), ResourceState::Failed);
+    }
+}
+
+#[test]
+fn pod_failure_detects_oom_killed() {
+    let pod = json!({ "status": { "containerStatuses": [{
+        "name": "worker",
+        "state": { "waiting": { "reason": "CrashLoopBackOff" } },
+        "lastState": { "terminated": { "reason": "OOMKilled" } }
+    }] } });
+    assert_eq!(
+        pod_failure(&pod).as_deref(),
+        Some("container worker: OOMKilled")
+    );
+}
+
+#[test]
+fn pod_failure_ignores_recovered_oom_kill() {
+    let pod = json!({ "status": {
+        "phase": "Running",
+        "containerStatuses": [{
+            "name": "worker",
+            "ready": true,
+            "state": { "running": {} },
+            "lastState": { "terminated": { "reason": "OOMKilled" } }
+        }]
+    } });
+    assert_eq!(pod_failure(&pod), None);
+}
+
+#[test]
+fn pod_failure_detects_unschedulable() {
+    let pod = json!({ "status": {
+        "phase": "Pending",
+        "conditions": [{
+            "type": "PodScheduled",
+            "status": "False",
+            "reason": "Unschedulable",
+            "message": "0/3 nodes are available"
+        }]
+    } });
+    assert_eq!(
+        pod_failure(&pod).as_deref(),
+        Some("Unschedulable: 0/3 nodes are available")
+    );
+}
+
+#[test]
+fn healthy_pod_has_no_failure() {
+    let pod = json!({ "status": { "phase": "Running", "containerStatuses": [
+        { "name": "app", "ready": true, "state": { "running": {} } }
+    ] } });
+    assert_eq!(pod_failure(&pod), None);
+}
+
+#[test]
+fn pod_selector_from_workload_match_labels() {
+    let yaml = "apiVersion: apps/v1\nkind: Deployment\nmetadata:\n  name: web\nspec:\n  selector:\n    matchLabels:\n      app: web\n      tier: frontend\n";
+    let resource = parse_manifests(yaml).unwrap().remove(0);
+    assert_eq!(
+        pod_selector(&resource).as_deref(),
+        Some("app=web,tier=frontend")
+    );
+
+    let service = descriptor("Service", "web", "prod", None);
+    assert_eq!(pod_selector(&service), None);
+}
+
+#[test]
+fn describe_container_includes_state_and_last_termination() {
+    let cs: ContainerStatus = serde_json::from_value(json!({
+        "name": "app",
+        "image": "app:1",
+        "imageID": "",
+        "ready": false,
+        "restartCount": 4,
+        "state": { "waiting": { "reason": "CrashLoopBackOff" } },
+        "lastState": { "terminated": { "reason": "Error", "exitCode": 137 } }
+    }))
+    .unwrap();
+    assert_eq!(
+        describe_container(&cs),
+        "container app: waiting (CrashLoopBackOff), ready=false, restarts=4, last terminated: Error (exit code 137)"
+    );
+}
+
+#[test]
+fn describe_container_running() {
+    let cs: ContainerStatus = serde_json::from_value(json!({
+        "name": "sidecar",
+        "image": "proxy:1",
+        "imageID": "",
+        "ready": true,
+        "restartCount": 0,
+        "state": { "running": {} }
+    }))
+    .unwrap();
+    assert_eq!(
+        describe_container(&cs),
+        "container sidecar: running, ready=true, restarts=0"
+    );
+}
+
+#[test]
+fn deployment_required_rollout_none_is_ready_immediately() {
+    let status = json!({
+        "metadata": { "annotations": { "boom.io/required-rollout": "none" } },
+        "spec": { "replicas": 3 },
+        "status": {}
+    });
+    assert_eq!(is_ready("Deployment", &status), ResourceState::Ready);
+}
+
+#[test]
+fn deployment_required_rollout_max_unavailable() {
+    let status = |available: i64| {
+        json!({
+            "metadata": { "annotations": { "boom.io/required-rollout": "maxUnavailable" } },
+            "spec": {
+                "replicas": 4,
+                "strategy": { "rollingUpdate": { "maxUnavailable": 1 } }
+            },
+            "status": { "availableReplicas": available, "updatedReplicas": 4 }
+        })
+    };
+    assert_eq!(is_ready("Deployment", &status(3)), ResourceState::Ready);
+    assert_eq!(is_ready("Deployment", &status(2)), ResourceState::NotReady);
+}
+
+#[test]
+fn deployment_required_rollout_percent() {
+    let status = json!({
+        "metadata": { "annotations": { "boom.io/required-rollout": "50%" } },
+        "spec": { "replicas": 10 },
+        "status": { "availableReplicas": 5, "updatedReplicas": 5 }
+    });
+    assert_eq!(is_ready("Deployment", &status), ResourceState::Ready);
+}
+
+#[test]
+fn current_pod_hash_uses_newest_replicaset() {
+    let deployment = json!({
+        "metadata": { "generation": 2 },
+        "status": { "observedGeneration": 2 }
+    });
+    let replicaset = |revision: &str, hash: &str| {
+        json!({ "metadata": {
+            "annotations": { "deployment.kubernetes.io/revision": revision },
+            "labels": { "pod-template-hash": hash }
+        } })
+    };
+    let owned = [replicaset("1", "old"), replicaset("2", "new")];
+    assert_eq!(
+        current_pod_hash("Deployment", &deployment, &owned).as_deref(),
+        Some("new")
+    );
+}
+
+#[test]
+fn current_pod_hash_waits_for_observed_generation() {
+    let deployment = json!({
+        "metadata": { "generation": 3 },
+        "status": { "observedGeneration": 2 }
+    });
+    let owned = [json!({ "metadata": {
+        "annotations": { "deployment.kubernetes.io/revision": "2" },
+        "labels": { "pod-template-hash": "old" }
+    } })];
+    assert_eq!(current_pod_hash("Deployment", &deployment, &owned), None);
+}
+
+#[test]
+fn current_pod_hash_for_statefulset_and_daemonset() {
+    let statefulset = json!({ "status": { "updateRevision": "db-7c9" } });
+    assert_eq!(
+        current_pod_hash("StatefulSet", &statefulset, &[]).as_deref(),
+        Some("db-7c9")
+    );
+
+    let revision = |revision: i64, hash: &str| {
+        json!({
+            "metadata": { "labels": { "controller-revision-hash": hash } },
+            "revision": revision
+        })
+    };
+    let owned = [revision(4, "b2"), revision(3, "a1")];
+    assert_eq!(
+        current_pod_hash("DaemonSet", &json!({}), &owned).as_deref(),
+        Some("b2")
+    );
+}
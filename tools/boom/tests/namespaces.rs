@@ -0,0 +1,79 @@
+use std::collections::BTreeSet;
+
+mod common;
+
+use boom::manifest::parse_manifests;
+use boom::namespaces::{check_allowed, touched, undeclared};
+use common::catalog;
+
+const MANIFESTS: &str = "\
+apiVersion: v1
+kind: Namespace
+metadata:
+  name: team-a
+---
+apiVersion: rbac.authorization.k8s.io/v1
+kind: ClusterRole
+metadata:
+  name: reader
+---
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: config
+  namespace: team-a
+---
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  namespace: team-b
+";
+
+fn set(names: &[&str]) -> BTreeSet<String> {
+    names.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn touched_collects_each_resource_namespace() {
+    let resources = parse_manifests(MANIFESTS).unwrap();
+    assert_eq!(
+        touched(&catalog(), &resources, None),
+        Ok(set(&["team-a", "team-b"]))
+    );
+}
+
+#[test]
+fn touched_uses_default_for_unset_namespaces() {
+    let yaml =
+        format!("{MANIFESTS}---\napiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: shared\n");
+    let resources = parse_manifests(&yaml).unwrap();
+    assert_eq!(
+        touched(&catalog(), &resources, Some("home")),
+        Ok(set(&["home", "team-a", "team-b"]))
+    );
+}
+
+#[test]
+fn touched_requires_a_namespace_without_default() {
+    let yaml =
+        format!("{MANIFESTS}---\napiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: shared\n");
+    let resources = parse_manifests(&yaml).unwrap();
+    let err = touched(&catalog(), &resources, None).unwrap_err();
+    assert!(err.contains("ConfigMap/shared"), "{err}");
+}
+
+#[test]
+fn check_allowed_rejects_unlisted_namespaces() {
+    let touched = set(&["team-a", "team-b"]);
+    assert!(check_allowed(&touched, &["team-a".to_string(), "team-b".to_string()]).is_ok());
+    let err = check_allowed(&touched, &["team-a".to_string()]).unwrap_err();
+    assert!(err.ends_with("team-b"), "{err}");
+}
+
+#[test]
+fn undeclared_skips_namespaces_in_manifests() {
+    let resources = parse_manifests(MANIFESTS).unwrap();
+    let touched = set(&["team-a", "team-b"]);
+    assert_eq!(undeclared(&touched, &resources), ["team-b"]);
+}
@@ -0,0 +1,42 @@
+use boom::output::{event_json, summary_json};
+use serde_json::json;
+
+#[test]
+fn event_json_tags_fields_with_event_name() {
+    let event = event_json(
+        "state",
+        json!({ "resource": "Deployment/web", "state": "Ready" }),
+    );
+    assert_eq!(
+        event,
+        json!({ "event": "state", "resource": "Deployment/web", "state": "Ready" })
+    );
+}
+
+#[test]
+fn summary_json_lists_resources() {
+    let rows = vec![
+        (
+            "web".to_string(),
+            "Deployment".to_string(),
+            "Ready".to_string(),
+            "4.2s".to_string(),
+        ),
+        (
+            "config".to_string(),
+            "ConfigMap".to_string(),
+            "OK".to_string(),
+            "0.1s".to_string(),
+        ),
+    ];
+    assert_eq!(
+        summary_json(&rows),
+        json!({
+            "event": "summary",
+            "resources": [
+                { "name": "web", "kind": "Deployment", "status": "Ready", "duration": "4.2s" },
+                { "name": "config", "kind": "ConfigMap", "status": "OK", "duration": "0.1s" }
+            ]
+        })
+    );
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use boom::manifest::ResourceDescriptor;
+use boom::phases::{effective_phases, plan, split_dependencies};
+use common::resource;
+
+#[test]
+fn resources_default_to_phase_zero() {
+    let resources = vec![
+        resource("ConfigMap", "a", &[]),
+        resource("Deployment", "b", &[]),
+    ];
+    assert_eq!(effective_phases(&resources), Ok(vec![0, 0]));
+}
+
+#[test]
+fn explicit_phase_annotation() {
+    let resources = vec![
+        resource("Deployment", "web", &[("boom.io/phase", "10")]),
+        resource("Job", "migrate", &[("boom.io/phase", "5")]),
+    ];
+    assert_eq!(effective_phases(&resources), Ok(vec![10, 5]));
+}
+
+#[test]
+fn depends_on_places_resource_after_dependencies() {
+    let resources = vec![
+        resource(
+            "Deployment",
+            "web",
+            &[("boom.io/depends-on", "Job/migrate, ConfigMap/config")],
+        ),
+        resource(
+            "Job",
+            "migrate",
+            &[("boom.io/depends-on", "ConfigMap/config")],
+        ),
+        resource("ConfigMap", "config", &[]),
+    ];
+    assert_eq!(effective_phases(&resources), Ok(vec![2, 1, 0]));
+}
+
+#[test]
+fn depends_on_respects_later_explicit_phase() {
+    let resources = vec![
+        resource("Job", "migrate", &[("boom.io/phase", "3")]),
+        resource(
+            "Deployment",
+            "web",
+            &[("boom.io/depends-on", "Job/migrate")],
+        ),
+    ];
+    assert_eq!(effective_phases(&resources), Ok(vec![3, 4]));
+}
+
+#[test]
+fn unknown_dependency_is_error() {
+    let resources = vec![resource(
+        "Deployment",
+        "web",
+        &[("boom.io/depends-on", "Job/missing")],
+    )];
+    let err = effective_phases(&resources).unwrap_err();
+    assert!(err.contains("Job/missing"), "{err}");
+}
+
+#[test]
+fn dependency_cycle_is_error() {
+    let resources = vec![
+        resource("Job", "a", &[("boom.io/depends-on", "Job/b")]),
+        resource("Job", "b", &[("boom.io/depends-on", "Job/a")]),
+    ];
+    let err = effective_phases(&resources).unwrap_err();
+    assert!(err.contains("cycle"), "{err}");
+}
+
+#[test]
+fn invalid_phase_is_error() {
+    let resources = vec![resource("Job", "a", &[("boom.io/phase", "first")])];
+    assert!(effective_phases(&resources).is_err());
+}
+
+#[test]
+fn plan_groups_in_ascending_phase_order() {
+    let resources = vec![
+        resource(
+            "Deployment",
+            "web",
+            &[("boom.io/depends-on", "Job/migrate")],
+        ),
+        resource("ConfigMap", "config", &[]),
+        resource("Job", "migrate", &[]),
+    ];
+    let phases = plan(&resources).unwrap();
+    let names: Vec<(i64, Vec<&str>)> = phases
+        .iter()
+        .map(|(phase, members)| (*phase, members.iter().map(|r| r.name.as_str()).collect()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![(0, vec!["config", "migrate"]), (1, vec!["web"])]
+    );
+}
+
+#[test]
+fn depends_on_resolves_within_the_dependent_namespace() {
+    let in_namespace = |mut r: ResourceDescriptor, namespace: &str| {
+        r.namespace = Some(namespace.to_string());
+        r
+    };
+    let resources = vec![
+        in_namespace(resource("Job", "migrate", &[("boom.io/phase", "5")]), "a"),
+        in_namespace(resource("Job", "migrate", &[]), "b"),
+        in_namespace(
+            resource(
+                "Deployment",
+                "web",
+                &[("boom.io/depends-on", "Job/migrate")],
+            ),
+            "b",
+        ),
+        in_namespace(
+            resource(
+                "Deployment",
+                "web",
+                &[("boom.io/depends-on", "a/Job/migrate")],
+            ),
+            "c",
+        ),
+    ];
+    assert_eq!(effective_phases(&resources), Ok(vec![5, 0, 1, 6]));
+}
+
+#[test]
+fn split_dependencies_pulls_tier_zero_and_one() {
+    let resources = vec![
+        resource("Deployment", "web", &[]),
+        resource("ConfigMap", "config", &[]),
+        resource("ServiceAccount", "web", &[]),
+    ];
+    let (dependencies, phases) = split_dependencies(vec![(0, resources)]);
+
+    let names: Vec<_> = dependencies.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["config", "web"]);
+    assert_eq!(phases.len(), 1);
+    assert!(phases[0].1.iter().all(|r| r.kind == "Deployment"));
+}
+
+#[test]
+fn split_dependencies_drops_emptied_phases() {
+    let config = vec![resource("ConfigMap", "config", &[])];
+    let (dependencies, phases) = split_dependencies(vec![(0, config)]);
+    assert_eq!(dependencies.len(), 1);
+    assert!(phases.is_empty());
+}
@@ -1,7 +1,8 @@
-use boom::prune::{ResourceDescriptor, identify_stale};
+use boom::prune::{ResourceDescriptor, identify_stale, prunable_kinds};
 
 fn descriptor(name: &str, kind: &str, namespace: &str) -> ResourceDescriptor {
     ResourceDescriptor {
+        api_version: "apps/v1".to_string(),
         name: name.to_string(),
         kind: kind.to_string(),
         namespace: namespace.to_string(),
@@ -65,3 +66,43 @@ fn all_cluster_resources_stale() {
         stale.len()
     );
 }
+
+#[test]
+fn prunable_kinds_include_builtins() {
+    let kinds = prunable_kinds(&[]);
+    assert!(
+        kinds
+            .iter()
+            .any(|(v, k)| v == "apps/v1" && k == "Deployment")
+    );
+    assert!(kinds.iter().any(|(v, k)| v == "v1" && k == "ConfigMap"));
+}
+
+#[test]
+fn prunable_kinds_include_deployed_custom_kinds_once() {
+    let mut cert = descriptor("tls", "Certificate", "default");
+    cert.api_version = "cert-manager.io/v1".to_string();
+    let deployed = vec![cert.clone(), cert, descriptor("a", "Deployment", "default")];
+
+    let kinds = prunable_kinds(&deployed);
+    let certs: Vec<_> = kinds.iter().filter(|(_, k)| k == "Certificate").collect();
+    assert_eq!(certs.len(), 1, "custom kind should be listed once");
+    assert_eq!(certs[0].0, "cert-manager.io/v1");
+    assert_eq!(
+        kinds.iter().filter(|(_, k)| k == "Deployment").count(),
+        1,
+        "built-in kinds should not be duplicated"
+    );
+}
+
+#[test]
+fn identify_stale_compares_namespaces() {
+    let deployed = vec![descriptor("a", "Deployment", "team-a")];
+    let existing = vec![
+        descriptor("a", "Deployment", "team-a"),
+        descriptor("a", "Deployment", "team-b"),
+    ];
+    let stale = identify_stale(&deployed, &existing);
+    assert_eq!(stale.len(), 1);
+    assert_eq!(stale[0].namespace, "team-b");
+}
@@ -1,12 +1,17 @@
-use std::collections::HashMap;
 use std::io::Write;
 
+use boom::render::RenderOptions;
+use serde_json::json;
+
 use tempfile::TempDir;
 
 fn make_dir_with_files(files: &[(&str, &str)]) -> TempDir {
     let dir = TempDir::new().expect("failed to create temp dir");
     for (name, content) in files {
         let path = dir.path().join(name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("failed to create parent dir");
+        }
         let mut f = std::fs::File::create(&path).expect("failed to create file");
         f.write_all(content.as_bytes())
             .expect("failed to write file");
@@ -18,18 +23,19 @@ fn make_dir_with_files(files: &[(&str, &str)]) -> TempDir {
 fn plain_yaml_passes_through() {
     let yaml = "apiVersion: v1\nkind: Service\n";
     let dir = make_dir_with_files(&[("svc.yml", yaml)]);
-    let templates = boom::render::load_templates(dir.path());
-    let output = boom::render::render_templates(&templates, &HashMap::new()).unwrap();
+    let templates = boom::render::load_templates(dir.path()).unwrap();
+    let output =
+        boom::render::render_templates(&templates, &json!({}), &RenderOptions::default()).unwrap();
     assert_eq!(output.trim(), yaml.trim());
 }
 
 #[test]
 fn j2_file_renders_variable() {
     let dir = make_dir_with_files(&[("svc.yml.j2", "value: {{ greeting }}\n")]);
-    let mut bindings = HashMap::new();
-    bindings.insert("greeting".to_string(), "hello".to_string());
-    let templates = boom::render::load_templates(dir.path());
-    let output = boom::render::render_templates(&templates, &bindings).unwrap();
+    let bindings = json!({ "greeting": "hello" });
+    let templates = boom::render::load_templates(dir.path()).unwrap();
+    let output =
+        boom::render::render_templates(&templates, &bindings, &RenderOptions::default()).unwrap();
     assert!(
         output.contains("value: hello"),
         "expected 'value: hello' in output: {output}"
@@ -39,10 +45,10 @@ fn j2_file_renders_variable() {
 #[test]
 fn if_block_renders() {
     let dir = make_dir_with_files(&[("cfg.yml.j2", "{% if enabled %}flag: true{% endif %}\n")]);
-    let mut bindings = HashMap::new();
-    bindings.insert("enabled".to_string(), "true".to_string());
-    let templates = boom::render::load_templates(dir.path());
-    let output = boom::render::render_templates(&templates, &bindings).unwrap();
+    let bindings = json!({ "enabled": "true" });
+    let templates = boom::render::load_templates(dir.path()).unwrap();
+    let output =
+        boom::render::render_templates(&templates, &bindings, &RenderOptions::default()).unwrap();
     assert!(
         output.contains("flag: true"),
         "expected 'flag: true' in output: {output}"
@@ -52,18 +58,18 @@ fn if_block_renders() {
 #[test]
 fn missing_variable_is_error() {
     let dir = make_dir_with_files(&[("bad.yml.j2", "value: {{ undefined_var }}\n")]);
-    let templates = boom::render::load_templates(dir.path());
-    let result = boom::render::render_templates(&templates, &HashMap::new());
+    let templates = boom::render::load_templates(dir.path()).unwrap();
+    let result = boom::render::render_templates(&templates, &json!({}), &RenderOptions::default());
     assert!(result.is_err(), "expected error for missing variable");
 }
 
 #[test]
 fn multiple_files_all_rendered() {
     let dir = make_dir_with_files(&[("a.yml", "kind: A\n"), ("b.yml.j2", "kind: {{ bkind }}\n")]);
-    let mut bindings = HashMap::new();
-    bindings.insert("bkind".to_string(), "B".to_string());
-    let templates = boom::render::load_templates(dir.path());
-    let output = boom::render::render_templates(&templates, &bindings).unwrap();
+    let bindings = json!({ "bkind": "B" });
+    let templates = boom::render::load_templates(dir.path()).unwrap();
+    let output =
+        boom::render::render_templates(&templates, &bindings, &RenderOptions::default()).unwrap();
     assert!(
         output.contains("kind: A"),
         "expected 'kind: A' in output: {output}"
@@ -77,14 +83,154 @@ fn multiple_files_all_rendered() {
 #[test]
 fn parse_bindings_splits_on_first_equals() {
     let pairs = vec!["key=value".to_string(), "a=b=c".to_string()];
-    let map = boom::render::parse_bindings(&pairs);
-    assert_eq!(map.get("key").map(String::as_str), Some("value"));
-    assert_eq!(map.get("a").map(String::as_str), Some("b=c"));
+    let map = boom::render::parse_bindings(&pairs).unwrap();
+    assert_eq!(map["key"], "value");
+    assert_eq!(map["a"], "b=c");
 }
 
 #[test]
 fn load_bindings_file_reads_yaml() {
     let dir = make_dir_with_files(&[("bindings.yml", "greeting: hello\n")]);
-    let map = boom::render::load_bindings_file(&dir.path().join("bindings.yml"));
-    assert_eq!(map.get("greeting").map(String::as_str), Some("hello"));
+    let map = boom::render::load_bindings_file(&dir.path().join("bindings.yml")).unwrap();
+    assert_eq!(map["greeting"], "hello");
+}
+
+#[test]
+fn load_bindings_file_keeps_structured_values() {
+    let dir = make_dir_with_files(&[(
+        "values.yml",
+        "replicas: 3\nenabled: true\nports: [80, 443]\ndb:\n  host: pg\n",
+    )]);
+    let map = boom::render::load_bindings_file(&dir.path().join("values.yml")).unwrap();
+    assert_eq!(
+        map,
+        json!({
+            "replicas": 3,
+            "enabled": true,
+            "ports": [80, 443],
+            "db": { "host": "pg" }
+        })
+    );
+}
+
+#[test]
+fn parse_bindings_builds_nested_paths() {
+    let pairs = vec!["db.host=pg".to_string(), "db.port=5432".to_string()];
+    let map = boom::render::parse_bindings(&pairs).unwrap();
+    assert_eq!(map, json!({ "db": { "host": "pg", "port": "5432" } }));
+}
+
+#[test]
+fn load_bindings_merges_files_and_overrides_deeply() {
+    let dir = make_dir_with_files(&[
+        ("base.yml", "db:\n  host: pg\n  port: 5432\nimage: app:1\n"),
+        ("prod.yml", "db:\n  host: pg-prod\n"),
+    ]);
+    let files = vec![dir.path().join("base.yml"), dir.path().join("prod.yml")];
+    let overrides = vec!["image=app:2".to_string()];
+    let map = boom::render::load_bindings(&files, &overrides).unwrap();
+    assert_eq!(
+        map,
+        json!({ "db": { "host": "pg-prod", "port": 5432 }, "image": "app:2" })
+    );
+}
+
+#[test]
+fn for_loop_over_structured_bindings() {
+    let dir = make_dir_with_files(&[(
+        "ports.yml.j2",
+        "ports:\n{% for p in ports %}  - {{ p.name }}: {{ p.port }}\n{% endfor %}",
+    )]);
+    let bindings =
+        json!({ "ports": [{ "name": "http", "port": 80 }, { "name": "https", "port": 443 }] });
+    let templates = boom::render::load_templates(dir.path()).unwrap();
+    let output =
+        boom::render::render_templates(&templates, &bindings, &RenderOptions::default()).unwrap();
+    assert!(output.contains("  - http: 80\n  - https: 443"), "{output}");
+}
+
+#[test]
+fn nested_directories_render_in_path_order() {
+    let dir = make_dir_with_files(&[
+        ("b.yml", "kind: B\n"),
+        ("apps/web/deploy.yml.j2", "kind: {{ kind }}\n"),
+        ("apps/api.yml", "kind: Api\n"),
+        ("a.yml", "kind: A\n"),
+        (".hidden/skip.yml", "kind: Hidden\n"),
+    ]);
+    let templates = boom::render::load_templates(dir.path()).unwrap();
+    let names: Vec<_> = templates.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["a.yml", "apps/api.yml", "apps/web/deploy.yml.j2", "b.yml"]
+    );
+
+    let files = boom::render::render_files(
+        &templates,
+        &json!({ "kind": "Web" }),
+        &RenderOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(files[2].name, "apps/web/deploy.yml.j2");
+    assert_eq!(files[2].content, "kind: Web");
+}
+
+#[test]
+fn partials_are_includable_but_not_rendered() {
+    let dir = make_dir_with_files(&[
+        ("_partials/labels.j2", "app: {{ app }}"),
+        (
+            "_partials/macros/ports.j2",
+            "{% macro port(n) %}- {{ n }}{% endmacro %}",
+        ),
+        (
+            "deploy.yml.j2",
+            "{% import \"macros/ports.j2\" as m %}labels:\n  {% include \"labels.j2\" %}\nports:\n  {{ m.port(80) }}\n",
+        ),
+    ]);
+    let templates = boom::render::load_templates(dir.path()).unwrap();
+    let files = boom::render::render_files(
+        &templates,
+        &json!({ "app": "web" }),
+        &RenderOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        files.len(),
+        1,
+        "partials should not be emitted as documents"
+    );
+    assert!(
+        files[0].content.contains("  app: web"),
+        "{}",
+        files[0].content
+    );
+    assert!(files[0].content.contains("  - 80"), "{}", files[0].content);
+}
+
+#[test]
+fn render_error_names_nested_template() {
+    let dir = make_dir_with_files(&[("apps/bad.yml.j2", "value: {{ missing }}\n")]);
+    let templates = boom::render::load_templates(dir.path()).unwrap();
+    let err = boom::render::render_templates(&templates, &json!({}), &RenderOptions::default())
+        .unwrap_err();
+    assert!(err.contains("apps/bad.yml.j2"), "{err}");
+}
+
+#[test]
+fn parse_bindings_rejects_missing_equals() {
+    let pairs = vec!["image".to_string()];
+    assert!(matches!(
+        boom::render::parse_bindings(&pairs),
+        Err(boom::error::BoomError::Usage(_))
+    ));
+}
+
+#[test]
+fn load_templates_missing_dir_is_render_error() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(matches!(
+        boom::render::load_templates(&dir.path().join("missing")),
+        Err(boom::error::BoomError::Render(_))
+    ));
 }
@@ -1,4 +1,7 @@
+use boom::monitor::pod_selector;
 use boom::restart;
+use kube::api::DynamicObject;
+use serde_json::json;
 
 #[test]
 fn build_restart_patch_sets_annotation_on_pod_template() {
@@ -25,3 +28,17 @@ fn build_restart_patch_has_no_top_level_metadata() {
         "patch should not contain a top-level metadata key"
     );
 }
+
+#[test]
+fn restarted_descriptor_keeps_the_live_selector() {
+    let live: DynamicObject = serde_json::from_value(json!({
+        "apiVersion": "apps/v1",
+        "kind": "Deployment",
+        "metadata": { "name": "web", "namespace": "prod" },
+        "spec": { "selector": { "matchLabels": { "app": "web" } } }
+    }))
+    .unwrap();
+    let resource = restart::restarted_descriptor("apps/v1", "Deployment", "prod", &live).unwrap();
+    assert_eq!(resource.name, "web");
+    assert_eq!(pod_selector(&resource).as_deref(), Some("app=web"));
+}
@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+
+use boom::revert::{
+    REVISION_ANNOTATION, is_revertible, previous_controller_revision, previous_replicaset,
+    template_patch,
+};
+use k8s_openapi::api::apps::v1::{ControllerRevision, ReplicaSet};
+use k8s_openapi::api::core::v1::PodTemplateSpec;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference};
+
+fn meta(name: &str, owner_uid: &str, annotations: &[(&str, &str)]) -> ObjectMeta {
+    ObjectMeta {
+        name: Some(name.to_string()),
+        owner_references: Some(vec![OwnerReference {
+            uid: owner_uid.to_string(),
+            ..Default::default()
+        }]),
+        annotations: Some(
+            annotations
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect(),
+        ),
+        ..Default::default()
+    }
+}
+
+fn replicaset(name: &str, owner_uid: &str, revision: &str) -> ReplicaSet {
+    ReplicaSet {
+        metadata: meta(name, owner_uid, &[(REVISION_ANNOTATION, revision)]),
+        ..Default::default()
+    }
+}
+
+fn controller_revision(name: &str, owner_uid: &str, revision: i64) -> ControllerRevision {
+    ControllerRevision {
+        metadata: meta(name, owner_uid, &[]),
+        revision,
+        data: None,
+    }
+}
+
+#[test]
+fn only_workloads_are_revertible() {
+    assert!(is_revertible("Deployment"));
+    assert!(is_revertible("StatefulSet"));
+    assert!(is_revertible("DaemonSet"));
+    assert!(!is_revertible("Job"));
+    assert!(!is_revertible("ConfigMap"));
+}
+
+#[test]
+fn previous_replicaset_picks_highest_older_owned_revision() {
+    let sets = vec![
+        replicaset("web-1", "uid-a", "1"),
+        replicaset("web-2", "uid-a", "2"),
+        replicaset("web-3", "uid-a", "3"),
+        replicaset("other-2", "uid-b", "2"),
+    ];
+    let previous = previous_replicaset(3, "uid-a", &sets).unwrap();
+    assert_eq!(previous.metadata.name.as_deref(), Some("web-2"));
+}
+
+#[test]
+fn previous_replicaset_requires_history() {
+    let sets = vec![replicaset("web-1", "uid-a", "1")];
+    assert!(previous_replicaset(1, "uid-a", &sets).is_none());
+    assert!(previous_replicaset(2, "uid-b", &sets).is_none());
+}
+
+#[test]
+fn previous_controller_revision_ignores_other_owners() {
+    let revisions = vec![
+        controller_revision("db-1", "uid-a", 1),
+        controller_revision("db-2", "uid-a", 2),
+        controller_revision("cache-4", "uid-b", 4),
+    ];
+    let previous = previous_controller_revision(3, "uid-a", &revisions).unwrap();
+    assert_eq!(previous.metadata.name.as_deref(), Some("db-2"));
+    assert!(previous_controller_revision(1, "uid-a", &revisions).is_none());
+}
+
+#[test]
+fn template_patch_replaces_template_without_hash_label() {
+    let template = PodTemplateSpec {
+        metadata: Some(ObjectMeta {
+            labels: Some(BTreeMap::from([
+                ("app".to_string(), "web".to_string()),
+                ("pod-template-hash".to_string(), "abc123".to_string()),
+            ])),
+            ..Default::default()
+        }),
+        spec: None,
+    };
+    let patch = template_patch(&template);
+    let patched = &patch["spec"]["template"];
+    assert_eq!(patched["$patch"], "replace");
+    assert_eq!(patched["metadata"]["labels"]["app"], "web");
+    assert!(
+        patched["metadata"]["labels"]
+            .get("pod-template-hash")
+            .is_none()
+    );
+}
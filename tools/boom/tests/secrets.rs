@@ -0,0 +1,137 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use boom::secrets::{
+    REDACTED, decrypt_document, decrypt_value, load_secrets_file, redact, secret_strings,
+};
+use crypto_box::aead::Aead;
+use crypto_box::{Nonce, PublicKey, SalsaBox, SecretKey};
+use serde_json::json;
+
+fn keypair(seed: u8) -> (SecretKey, PublicKey) {
+    let secret = SecretKey::from_bytes([seed; 32]);
+    let public = secret.public_key();
+    (secret, public)
+}
+
+fn encrypt(plaintext: &str, recipient: &PublicKey) -> String {
+    let (ephemeral, ephemeral_public) = keypair(7);
+    let nonce = [3u8; 24];
+    let boxed = SalsaBox::new(recipient, &ephemeral)
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_bytes())
+        .unwrap();
+    format!(
+        "EJ[1:{}:{}:{}]",
+        STANDARD.encode(ephemeral_public.as_bytes()),
+        STANDARD.encode(nonce),
+        STANDARD.encode(boxed)
+    )
+}
+
+#[test]
+fn decrypt_value_round_trip() {
+    let (secret, public) = keypair(1);
+    let encrypted = encrypt("hunter2", &public);
+    assert_eq!(decrypt_value(&encrypted, &secret).unwrap(), "hunter2");
+}
+
+#[test]
+fn decrypt_value_with_wrong_key_fails() {
+    let (_, public) = keypair(1);
+    let (other, _) = keypair(2);
+    let encrypted = encrypt("hunter2", &public);
+    let err = decrypt_value(&encrypted, &other).unwrap_err();
+    assert!(err.contains("decryption failed"), "{err}");
+}
+
+#[test]
+fn decrypt_value_rejects_malformed_input() {
+    let (secret, _) = keypair(1);
+    assert!(decrypt_value("EJ[1:abc]", &secret).is_err());
+    assert!(decrypt_value("EJ[2:a:b:c]", &secret).is_err());
+}
+
+#[test]
+fn decrypt_document_walks_nested_values() {
+    let (secret, public) = keypair(1);
+    let doc = json!({
+        "_public_key": hex::encode(public.as_bytes()),
+        "db": { "password": encrypt("pw", &public), "_user": "app" },
+        "tokens": [encrypt("t1", &public)],
+        "port": 5432
+    });
+    let decrypted = decrypt_document(&doc, &secret).unwrap();
+    assert_eq!(
+        decrypted,
+        json!({
+            "db": { "password": "pw", "_user": "app" },
+            "tokens": ["t1"],
+            "port": 5432
+        })
+    );
+}
+
+#[test]
+fn load_secrets_file_uses_key_file() {
+    let (secret, public) = keypair(1);
+    let dir = tempfile::TempDir::new().unwrap();
+    let secrets_path = dir.path().join("secrets.ejson");
+    let key_path = dir.path().join("key");
+    let doc = json!({
+        "_public_key": hex::encode(public.as_bytes()),
+        "api_key": encrypt("abc123", &public)
+    });
+    std::fs::write(&secrets_path, doc.to_string()).unwrap();
+    std::fs::write(&key_path, hex::encode(secret.to_bytes())).unwrap();
+
+    let decrypted = load_secrets_file(&secrets_path, Some(&key_path)).unwrap();
+    assert_eq!(decrypted, json!({ "api_key": "abc123" }));
+}
+
+#[test]
+fn redact_masks_plain_and_base64_values() {
+    let secrets =
+        secret_strings(&json!({ "password": "hunter2", "nested": { "token": "t0k3n-9" } }));
+    let text = format!(
+        "password: hunter2\nencoded: {}\ntoken: t0k3n-9\nother: safe\n",
+        STANDARD.encode("hunter2")
+    );
+    let redacted = redact(&text, &secrets);
+    assert_eq!(
+        redacted,
+        format!("password: {REDACTED}\nencoded: {REDACTED}\ntoken: {REDACTED}\nother: safe\n")
+    );
+}
+
+#[test]
+fn secret_strings_skip_plaintext_values() {
+    let secrets = secret_strings(&json!({
+        "_comment": "not encrypted by ejson",
+        "db": "db",
+        "nested": { "_user": "admin-user", "password": "hunter2" },
+        "list": ["1", "long-enough"]
+    }));
+    assert_eq!(secrets, ["db", "1", "long-enough", "hunter2"]);
+}
+
+#[test]
+fn redact_matches_whole_scalars_only() {
+    let secrets = secret_strings(&json!({ "flag": "1", "name": "db" }));
+    let text = "replicas: 1\nname: db\nhost: db-primary\nimage: db:1\nargs:\n  - \"db\"\n";
+    assert_eq!(
+        redact(text, &secrets),
+        format!(
+            "replicas: {REDACTED}\nname: {REDACTED}\nhost: db-primary\nimage: db:1\nargs:\n  - {REDACTED}\n"
+        )
+    );
+}
+
+#[test]
+fn redact_masks_diff_lines_and_block_scalars() {
+    let secrets = vec!["-----BEGIN KEY-----\nabc\n-----END KEY-----".to_string()];
+    let text =
+        "+  key: |\n+    -----BEGIN KEY-----\n+    abc\n+    -----END KEY-----\n-  key: old\n";
+    assert_eq!(
+        redact(text, &secrets),
+        format!("+  key: |\n+    {REDACTED}\n+    {REDACTED}\n+    {REDACTED}\n-  key: old\n")
+    );
+}
@@ -0,0 +1,197 @@
+use boom::manifest::parse_source;
+use boom::validate::{SchemaStore, validate_all};
+
+const DEPLOYMENT: &str = "\
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+spec:
+  replicas: 2
+  selector:
+    matchLabels:
+      app: web
+  template:
+    metadata:
+      labels:
+        app: web
+    spec:
+      containers:
+        - name: web
+          image: nginx
+          ports:
+            - containerPort: 80
+";
+
+const CRD: &str = "\
+apiVersion: apiextensions.k8s.io/v1
+kind: CustomResourceDefinition
+metadata:
+  name: widgets.example.com
+spec:
+  group: example.com
+  scope: Namespaced
+  names:
+    kind: Widget
+    plural: widgets
+  versions:
+    - name: v1
+      served: true
+      storage: true
+      schema:
+        openAPIV3Schema:
+          type: object
+          properties:
+            spec:
+              type: object
+              required: [size]
+              properties:
+                size:
+                  type: integer
+                color:
+                  type: string
+                  enum: [red, blue]
+                port:
+                  x-kubernetes-int-or-string: true
+                labels:
+                  type: object
+                  additionalProperties:
+                    type: string
+                extra:
+                  type: object
+                  x-kubernetes-preserve-unknown-fields: true
+";
+
+fn errors(source: &str, yaml: &str, schemas: &SchemaStore) -> Vec<String> {
+    let resources = parse_source(source, yaml).unwrap();
+    validate_all(&resources, schemas)
+        .errors
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn valid_deployment_passes() {
+    assert!(errors("web.yml", DEPLOYMENT, &SchemaStore::default()).is_empty());
+}
+
+#[test]
+fn unknown_field_reports_path_and_source() {
+    let yaml = DEPLOYMENT.replace("          image: nginx", "          imagee: nginx");
+    assert_eq!(
+        errors("web.yml.j2", &yaml, &SchemaStore::default()),
+        ["web.yml.j2: Deployment/web: spec.template.spec.containers[0].imagee: unknown field"]
+    );
+}
+
+#[test]
+fn wrong_type_reports_path() {
+    let yaml = DEPLOYMENT.replace("replicas: 2", "replicas: two");
+    let errors = errors("web.yml", &yaml, &SchemaStore::default());
+    assert_eq!(errors.len(), 1);
+    assert!(
+        errors[0].starts_with("web.yml: Deployment/web: spec.replicas: invalid type"),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn unknown_kind_is_unchecked() {
+    let yaml = "apiVersion: example.com/v1\nkind: Widget\nmetadata:\n  name: w\n";
+    let resources = parse_source("w.yml", yaml).unwrap();
+    let report = validate_all(&resources, &SchemaStore::default());
+    assert!(report.errors.is_empty());
+    assert_eq!(report.unchecked, ["Widget/w"]);
+}
+
+#[test]
+fn custom_resource_validated_against_crd_in_manifests() {
+    let widget = "\
+apiVersion: example.com/v1
+kind: Widget
+metadata:
+  name: w
+  labelz: {}
+spec:
+  colour: red
+  color: green
+  port: 8080
+  labels:
+    team: core
+  extra:
+    anything: [1, 2]
+";
+    let mut schemas = SchemaStore::default();
+    schemas.register_crds(&parse_source("crd.yml", CRD).unwrap());
+    let mut errors = errors("widget.yml", widget, &schemas);
+    errors.sort();
+    assert_eq!(
+        errors,
+        [
+            "widget.yml: Widget/w: metadata.labelz: unknown field",
+            "widget.yml: Widget/w: spec.color: \"green\" is not one of \"red\", \"blue\"",
+            "widget.yml: Widget/w: spec.colour: unknown field",
+            "widget.yml: Widget/w: spec.size: missing required field",
+        ]
+    );
+}
+
+#[test]
+fn custom_resource_type_errors() {
+    let widget = "\
+apiVersion: example.com/v1
+kind: Widget
+metadata:
+  name: w
+spec:
+  size: large
+  port: true
+  labels:
+    team: 1
+";
+    let mut schemas = SchemaStore::default();
+    schemas.register_crds(&parse_source("crd.yml", CRD).unwrap());
+    let mut errors = errors("widget.yml", widget, &schemas);
+    errors.sort();
+    assert_eq!(
+        errors,
+        [
+            "widget.yml: Widget/w: spec.labels.team: expected string",
+            "widget.yml: Widget/w: spec.port: expected an integer or string",
+            "widget.yml: Widget/w: spec.size: expected integer",
+        ]
+    );
+}
+
+#[test]
+fn load_dir_registers_crds() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("nested")).unwrap();
+    std::fs::write(dir.path().join("nested/widgets.yaml"), CRD).unwrap();
+    std::fs::write(dir.path().join("README.md"), "not yaml").unwrap();
+
+    let mut schemas = SchemaStore::default();
+    schemas.load_dir(dir.path()).unwrap();
+    assert!(schemas.crd_schema("example.com/v1", "Widget").is_some());
+    assert!(schemas.crd_schema("example.com/v2", "Widget").is_none());
+}
+
+#[test]
+fn load_dir_missing_directory_errors() {
+    let mut schemas = SchemaStore::default();
+    assert!(
+        schemas
+            .load_dir(std::path::Path::new("/nonexistent/crds"))
+            .is_err()
+    );
+}
+
+#[test]
+fn numeric_quantities_pass() {
+    let yaml = DEPLOYMENT.replace(
+        "          ports:\n",
+        "          resources:\n            limits:\n              cpu: 1\n              memory: 1073741824\n            requests:\n              cpu: 0.5\n          ports:\n",
+    );
+    assert!(errors("web.yml", &yaml, &SchemaStore::default()).is_empty());
+}